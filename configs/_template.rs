@@ -1,4 +1,8 @@
-use crate::{SwapLayoutState, TabPartState};
+use crate::command::CommandConfig;
+use crate::{
+    ClickTarget, ClientsState, CollapsedClick, CommandState, KeyHintState, MouseAction,
+    MouseButton, SwapLayoutState, TabOverflow, TabPartState, TabScroll,
+};
 use std::collections::HashMap;
 use zellij_tile::prelude::*;
 
@@ -6,7 +10,19 @@ pub struct Config<'a> {
     pub layout: &'a str,
     pub mode_layouts: HashMap<InputMode, &'a str>,
     pub tab_layouts: HashMap<TabPartState, &'a str>,
+    pub tab_overflow: TabOverflow,
+    pub tab_name_min_len: usize,
+    pub collapsed_click: CollapsedClick,
+    pub mouse_actions: Vec<(ClickTarget, MouseButton, MouseAction)>,
+    pub tab_scroll: TabScroll,
+    pub tab_scroll_wrap: bool,
     pub swap_layouts: HashMap<SwapLayoutState, &'a str>,
+    pub key_hints: Vec<(&'a str, &'a str)>,
+    pub key_hint_layouts: HashMap<KeyHintState, &'a str>,
+    pub utc_offset_minutes: i32,
+    pub commands: Vec<CommandConfig<'a>>,
+    pub command_layouts: HashMap<CommandState, &'a str>,
+    pub client_layouts: HashMap<ClientsState, &'a str>,
 }
 
 impl<'a> Default for Config<'a> {
@@ -36,11 +52,28 @@ impl<'a> Default for Config<'a> {
                 (TabPartState::ActiveSync, ""),
                 (TabPartState::LeftMoreTabs, ""),
                 (TabPartState::RightMoreTabs, ""),
+                (TabPartState::Pager, ""),
             ]),
+            tab_overflow: TabOverflow::Balanced,
+            tab_name_min_len: 4,
+            collapsed_click: CollapsedClick::Nearest,
+            mouse_actions: vec![(ClickTarget::Tab, MouseButton::Left, MouseAction::SwitchTab)],
+            tab_scroll: TabScroll::UpNext,
+            tab_scroll_wrap: false,
             swap_layouts: HashMap::from([
                 (SwapLayoutState::NonDirty, ""),
                 (SwapLayoutState::Dirty, ""),
             ]),
+            key_hints: vec![],
+            key_hint_layouts: HashMap::from([(KeyHintState::Full, ""), (KeyHintState::Short, "")]),
+            utc_offset_minutes: 0,
+            commands: vec![],
+            command_layouts: HashMap::from([
+                (CommandState::Success, ""),
+                (CommandState::Failure, ""),
+                (CommandState::Timeout, ""),
+            ]),
+            client_layouts: HashMap::from([(ClientsState::Tab, ""), (ClientsState::Session, "")]),
         }
     }
 }
@@ -1,4 +1,8 @@
-use crate::{SwapLayoutState, TabPartState};
+use crate::command::CommandConfig;
+use crate::{
+    ClickTarget, ClientsState, CollapsedClick, CommandState, KeyHintState, MouseAction,
+    MouseButton, SwapLayoutState, TabOverflow, TabPartState, TabScroll,
+};
 use std::collections::HashMap;
 use zellij_tile::prelude::*;
 
@@ -6,7 +10,19 @@ pub struct Config<'a> {
     pub layout: &'a str,
     pub mode_layouts: HashMap<InputMode, &'a str>,
     pub tab_layouts: HashMap<TabPartState, &'a str>,
+    pub tab_overflow: TabOverflow,
+    pub tab_name_min_len: usize,
+    pub collapsed_click: CollapsedClick,
+    pub mouse_actions: Vec<(ClickTarget, MouseButton, MouseAction)>,
+    pub tab_scroll: TabScroll,
+    pub tab_scroll_wrap: bool,
     pub swap_layouts: HashMap<SwapLayoutState, &'a str>,
+    pub key_hints: Vec<(&'a str, &'a str)>,
+    pub key_hint_layouts: HashMap<KeyHintState, &'a str>,
+    pub utc_offset_minutes: i32,
+    pub commands: Vec<CommandConfig<'a>>,
+    pub command_layouts: HashMap<CommandState, &'a str>,
+    pub client_layouts: HashMap<ClientsState, &'a str>,
 }
 
 impl<'a> Default for Config<'a> {
@@ -52,6 +68,30 @@ impl<'a> Default for Config<'a> {
                     TabPartState::ActiveSync,
                     "#[bg:green,fg:black,bold] #N (Sync) #[bg:black,fg:green]#[default]",
                 ),
+                (
+                    TabPartState::InactiveFullscreen,
+                    "#[bg:white,fg:black,bold] #N (Full) #[bg:black,fg:white]#[default]",
+                ),
+                (
+                    TabPartState::ActiveFullscreen,
+                    "#[bg:green,fg:black,bold] #N (Full) #[bg:black,fg:green]#[default]",
+                ),
+                (
+                    TabPartState::InactiveFloating,
+                    "#[bg:white,fg:black,bold] #N (Float) #[bg:black,fg:white]#[default]",
+                ),
+                (
+                    TabPartState::ActiveFloating,
+                    "#[bg:green,fg:black,bold] #N (Float) #[bg:black,fg:green]#[default]",
+                ),
+                (
+                    TabPartState::Activity,
+                    "#[bg:yellow,fg:black,bold] #N #[bg:black,fg:yellow]#[default]",
+                ),
+                (
+                    TabPartState::Bell,
+                    "#[bg:red,fg:black,bold] #N #[bg:black,fg:red]#[default]",
+                ),
                 (
                     TabPartState::LeftMoreTabs,
                     "#[bg:orange,fg:black,bold]#[fg:white] ← +#I #[bg:black,fg:orange]#[default]",
@@ -60,7 +100,23 @@ impl<'a> Default for Config<'a> {
                     TabPartState::RightMoreTabs,
                     "#[bg:orange,fg:black,bold]#[fg:white] +#I → #[bg:black,fg:orange]#[default]",
                 ),
+                (
+                    TabPartState::Pager,
+                    "#[bg:orange,fg:black,bold]#[fg:white] #I/#{tabs} #[bg:black,fg:orange]#[default]",
+                ),
             ]),
+            tab_overflow: TabOverflow::Balanced,
+            tab_name_min_len: 4,
+            collapsed_click: CollapsedClick::Nearest,
+            mouse_actions: vec![
+                (ClickTarget::Tab, MouseButton::Left, MouseAction::SwitchTab),
+                (ClickTarget::Tab, MouseButton::Right, MouseAction::RenameTab),
+                (ClickTarget::Mode, MouseButton::Left, MouseAction::ToggleLock),
+                (ClickTarget::SwapLayout, MouseButton::Left, MouseAction::NextSwapLayout),
+                (ClickTarget::SwapLayout, MouseButton::Right, MouseAction::PreviousSwapLayout),
+            ],
+            tab_scroll: TabScroll::UpNext,
+            tab_scroll_wrap: false,
             swap_layouts: HashMap::from([
                 (
                     SwapLayoutState::NonDirty,
@@ -71,6 +127,32 @@ impl<'a> Default for Config<'a> {
                     "#[bg:white,fg:black,bold] #N #[bg:black,fg:white]",
                 ),
             ]),
+            key_hints: vec![
+                ("SwitchToMode(Normal)", "NORMAL"),
+                ("SwitchToMode(Locked)", "LOCK"),
+                ("SwitchToMode(Pane)", "PANE"),
+                ("SwitchToMode(Tab)", "TAB"),
+                ("SwitchToMode(Resize)", "RESIZE"),
+                ("SwitchToMode(Move)", "MOVE"),
+                ("SwitchToMode(Scroll)", "SEARCH"),
+                ("SwitchToMode(Session)", "SESSION"),
+                ("Quit", "QUIT"),
+            ],
+            key_hint_layouts: HashMap::from([
+                (KeyHintState::Full, "#[bg:black,fg:gray] #K #[fg:white]#N "),
+                (KeyHintState::Short, "#[bg:black,fg:gray] #K "),
+            ]),
+            utc_offset_minutes: 0,
+            commands: vec![],
+            command_layouts: HashMap::from([
+                (CommandState::Success, "#[fg:white]#N "),
+                (CommandState::Failure, "#[fg:red]#N "),
+                (CommandState::Timeout, "#[fg:gray]#N "),
+            ]),
+            client_layouts: HashMap::from([
+                (ClientsState::Tab, "#[fg:magenta]●"),
+                (ClientsState::Session, "#[bg:black,fg:magenta] #I users "),
+            ]),
         }
     }
 }
@@ -1,4 +1,8 @@
-use crate::{SwapLayoutState, TabPartState};
+use crate::command::CommandConfig;
+use crate::{
+    ClickTarget, ClientsState, CollapsedClick, CommandState, KeyHintState, MouseAction,
+    MouseButton, SwapLayoutState, TabOverflow, TabPartState, TabScroll,
+};
 use std::collections::HashMap;
 use zellij_tile::prelude::*;
 
@@ -6,7 +10,19 @@ pub struct Config<'a> {
     pub layout: &'a str,
     pub mode_layouts: HashMap<InputMode, &'a str>,
     pub tab_layouts: HashMap<TabPartState, &'a str>,
+    pub tab_overflow: TabOverflow,
+    pub tab_name_min_len: usize,
+    pub collapsed_click: CollapsedClick,
+    pub mouse_actions: Vec<(ClickTarget, MouseButton, MouseAction)>,
+    pub tab_scroll: TabScroll,
+    pub tab_scroll_wrap: bool,
     pub swap_layouts: HashMap<SwapLayoutState, &'a str>,
+    pub key_hints: Vec<(&'a str, &'a str)>,
+    pub key_hint_layouts: HashMap<KeyHintState, &'a str>,
+    pub utc_offset_minutes: i32,
+    pub commands: Vec<CommandConfig<'a>>,
+    pub command_layouts: HashMap<CommandState, &'a str>,
+    pub client_layouts: HashMap<ClientsState, &'a str>,
 }
 
 impl<'a> Default for Config<'a> {
@@ -34,13 +50,72 @@ impl<'a> Default for Config<'a> {
                 (TabPartState::Active, "#[fg:green] #I #N #[default]"),
                 (TabPartState::InactiveSync, " #I #N 󰓦 #[default]"),
                 (TabPartState::ActiveSync, "#[fg:green] #I #N 󰓦 #[default]"),
-                (TabPartState::LeftMoreTabs, "#[fg:orange] ← +#I #[default]"),
-                (TabPartState::RightMoreTabs, "#[fg:orange] +#I → #[default]"),
+                (TabPartState::InactiveFullscreen, " #I #N 󰊓 #[default]"),
+                (
+                    TabPartState::ActiveFullscreen,
+                    "#[fg:green] #I #N 󰊓 #[default]",
+                ),
+                (TabPartState::InactiveFloating, " #I #N 󰖲 #[default]"),
+                (
+                    TabPartState::ActiveFloating,
+                    "#[fg:green] #I #N 󰖲 #[default]",
+                ),
+                (TabPartState::Activity, "#[fg:yellow] #I #N #[default]"),
+                (TabPartState::Bell, "#[fg:red] #I #N 󰂞 #[default]"),
+                (
+                    TabPartState::LeftMoreTabs,
+                    "#[fg:orange] ← #{collapsed.first}-#{collapsed.last} #[default]",
+                ),
+                (
+                    TabPartState::RightMoreTabs,
+                    "#[fg:orange] #{collapsed.first}-#{collapsed.last} → #[default]",
+                ),
+                (TabPartState::Pager, "#[fg:orange] #I/#{tabs} #[default]"),
             ]),
+            tab_overflow: TabOverflow::Balanced,
+            tab_name_min_len: 4,
+            collapsed_click: CollapsedClick::Nearest,
+            mouse_actions: vec![
+                (ClickTarget::Tab, MouseButton::Left, MouseAction::SwitchTab),
+                (ClickTarget::Tab, MouseButton::Right, MouseAction::RenameTab),
+                (
+                    ClickTarget::Mode,
+                    MouseButton::Left,
+                    MouseAction::ToggleLock,
+                ),
+            ],
+            tab_scroll: TabScroll::UpNext,
+            tab_scroll_wrap: false,
             swap_layouts: HashMap::from([
                 (SwapLayoutState::NonDirty, "#[fg:green]#N#[default]"),
                 (SwapLayoutState::Dirty, "#[default]#N#[default]"),
             ]),
+            key_hints: vec![
+                ("SwitchToMode(Normal)", "NORMAL"),
+                ("SwitchToMode(Locked)", "LOCK"),
+                ("SwitchToMode(Pane)", "PANE"),
+                ("SwitchToMode(Tab)", "TAB"),
+                ("SwitchToMode(Resize)", "RESIZE"),
+                ("SwitchToMode(Move)", "MOVE"),
+                ("SwitchToMode(Scroll)", "SEARCH"),
+                ("SwitchToMode(Session)", "SESSION"),
+                ("Quit", "QUIT"),
+            ],
+            key_hint_layouts: HashMap::from([
+                (KeyHintState::Full, "#[fg:gray] #K #[default]#N"),
+                (KeyHintState::Short, "#[fg:gray] #K#[default]"),
+            ]),
+            utc_offset_minutes: 0,
+            commands: vec![],
+            command_layouts: HashMap::from([
+                (CommandState::Success, "#N "),
+                (CommandState::Failure, "#[fg:red]#N#[default] "),
+                (CommandState::Timeout, "#[fg:gray]#N#[default] "),
+            ]),
+            client_layouts: HashMap::from([
+                (ClientsState::Tab, "#[fg:magenta]●#[default]"),
+                (ClientsState::Session, "#[fg:magenta] 󰀎 #I#[default]"),
+            ]),
         }
     }
 }
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use zellij_tile::prelude::*;
+
+/// Terminal pane id, title and whether it's exited.
+type PaneSnapshot = (u32, String, bool);
+
+/// Panes of a tab in the last update and its flags.
+#[derive(Default)]
+struct TabActivity {
+    panes: Vec<PaneSnapshot>,
+    activity: bool,
+    bell: bool,
+}
+
+/// Tracks activity and bell flags of background tabs.
+///
+/// Zellij doesn't report pane output to plugins, so activity is detected from changes of pane
+/// titles and set of panes, and bell from command panes which exited. Tabs are matched between
+/// updates by ids of their terminal panes, so flags follow tabs which are moved or shifted by a
+/// closed tab.
+#[derive(Default)]
+pub struct Activity {
+    /// Tabs by position in the last pane update.
+    tabs: HashMap<usize, TabActivity>,
+    /// Number of tabs changed since the last pane update, so positions are not known.
+    stale: bool,
+}
+
+impl Activity {
+    /// Compares panes with the previous update, returns true if any flag changed.
+    pub fn on_pane_update(&mut self, panes: &PaneManifest, active_tab_pos: usize) -> bool {
+        let flags = self.get_flags();
+        let mut prev_tabs = self.tabs.drain().map(|(_, t)| t).collect::<Vec<_>>();
+
+        for (pos, tab_panes) in &panes.panes {
+            let mut snapshot = tab_panes
+                .iter()
+                .filter(|p| !p.is_plugin)
+                .map(|p| (p.id, p.title.clone(), p.exited))
+                .collect::<Vec<_>>();
+            snapshot.sort();
+
+            let prev = prev_tabs
+                .iter()
+                .position(|t| t.panes.iter().any(|(id, _, _)| has_pane(&snapshot, *id)))
+                .map(|i| prev_tabs.swap_remove(i));
+            let mut tab = TabActivity {
+                panes: snapshot,
+                ..Default::default()
+            };
+
+            if let Some(prev) = prev.filter(|_| *pos != active_tab_pos) {
+                let has_exited = tab.panes.iter().any(|(id, _, exited)| {
+                    *exited
+                        && prev
+                            .panes
+                            .iter()
+                            .any(|(p_id, _, p_exited)| p_id == id && !p_exited)
+                });
+                tab.bell = prev.bell || has_exited;
+                tab.activity = prev.activity || (!has_exited && prev.panes != tab.panes);
+            }
+            self.tabs.insert(*pos, tab);
+        }
+
+        self.stale = false;
+        flags != self.get_flags()
+    }
+
+    /// Clears flags of the active tab, returns true if any flag changed. When the number of
+    /// tabs changes, flags are hidden until the next pane update matches tabs to positions.
+    pub fn on_tab_update(&mut self, active_tab_pos: usize, tabs_len: usize) -> bool {
+        if tabs_len != self.tabs.len() {
+            let changed = !self.stale && !self.get_flags().is_empty();
+            self.stale = true;
+            return changed;
+        }
+
+        match self.tabs.get_mut(&active_tab_pos) {
+            Some(tab) if tab.activity || tab.bell => {
+                tab.activity = false;
+                tab.bell = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn has_activity(&self, tab_pos: usize) -> bool {
+        !self.stale && self.tabs.get(&tab_pos).is_some_and(|t| t.activity)
+    }
+
+    pub fn has_bell(&self, tab_pos: usize) -> bool {
+        !self.stale && self.tabs.get(&tab_pos).is_some_and(|t| t.bell)
+    }
+
+    /// Returns positions of flagged tabs with their activity and bell flags.
+    fn get_flags(&self) -> Vec<(usize, bool, bool)> {
+        let flagged = self.tabs.iter().filter(|(_, t)| t.activity || t.bell);
+        let mut flags = flagged
+            .map(|(pos, t)| (*pos, t.activity, t.bell))
+            .collect::<Vec<_>>();
+        flags.sort();
+        flags
+    }
+}
+
+fn has_pane(panes: &[PaneSnapshot], id: u32) -> bool {
+    panes.iter().any(|(p_id, _, _)| *p_id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(id: u32, title: &str) -> PaneInfo {
+        PaneInfo {
+            id,
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns manifest of tabs with panes given by their ids and titles.
+    fn manifest(tabs: &[&[PaneInfo]]) -> PaneManifest {
+        let iter = tabs.iter().enumerate().map(|(pos, p)| (pos, p.to_vec()));
+        PaneManifest {
+            panes: iter.collect(),
+        }
+    }
+
+    /// Returns activity of three tabs with one pane each, the first one is active.
+    fn activity() -> Activity {
+        let mut activity = Activity::default();
+        let tabs = manifest(&[&[pane(1, "a")], &[pane(2, "b")], &[pane(3, "c")]]);
+        activity.on_pane_update(&tabs, 0);
+        activity
+    }
+
+    #[test]
+    fn flags_changed_title() {
+        let mut activity = activity();
+        let tabs = manifest(&[&[pane(1, "a")], &[pane(2, "b")], &[pane(3, "vim")]]);
+        assert!(activity.on_pane_update(&tabs, 0));
+        assert!(activity.has_activity(2));
+        assert!(!activity.has_activity(1) && !activity.has_bell(2));
+
+        assert!(!activity.on_tab_update(1, 3));
+        assert!(activity.on_tab_update(2, 3));
+        assert!(!activity.has_activity(2));
+    }
+
+    #[test]
+    fn rings_bell_of_exited_command() {
+        let mut activity = activity();
+        let exited = PaneInfo {
+            exited: true,
+            ..pane(2, "cargo test")
+        };
+        let tabs = manifest(&[&[pane(1, "a")], &[exited], &[pane(3, "c")]]);
+        assert!(activity.on_pane_update(&tabs, 0));
+        assert!(activity.has_bell(1));
+        assert!(!activity.has_activity(1));
+    }
+
+    #[test]
+    fn keeps_flags_of_tabs_after_closed_one() {
+        let mut activity = activity();
+        let tabs = manifest(&[&[pane(1, "a")], &[pane(2, "b")], &[pane(3, "vim")]]);
+        activity.on_pane_update(&tabs, 0);
+
+        // the second tab is closed, the tab update comes before the pane update
+        assert!(activity.on_tab_update(0, 2));
+        assert!(!activity.has_activity(1));
+        let tabs = manifest(&[&[pane(1, "a")], &[pane(3, "vim")]]);
+        assert!(activity.on_pane_update(&tabs, 0));
+        assert!(activity.has_activity(1));
+        assert!(!activity.has_activity(2));
+    }
+
+    #[test]
+    fn keeps_flags_of_reordered_tabs() {
+        let mut activity = activity();
+        let tabs = manifest(&[&[pane(1, "a")], &[pane(2, "b")], &[pane(3, "vim")]]);
+        activity.on_pane_update(&tabs, 0);
+
+        let tabs = manifest(&[&[pane(1, "a")], &[pane(3, "vim")], &[pane(2, "b")]]);
+        assert!(activity.on_pane_update(&tabs, 0));
+        assert!(activity.has_activity(1));
+        assert!(!activity.has_activity(2));
+    }
+}
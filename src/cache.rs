@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Cache of rendered values keeping entries used since the previous generation, so values of
+/// removed or changed inputs are dropped after one frame without explicit invalidation.
+pub struct Cache<K, V> {
+    prev: HashMap<K, V>,
+    curr: HashMap<K, V>,
+}
+
+impl<K, V> Default for Cache<K, V> {
+    fn default() -> Self {
+        Cache {
+            prev: HashMap::new(),
+            curr: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> {
+    /// Returns the cached value, the value is created by f if it's not used in the current or
+    /// the previous generation.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> V {
+        if let Some(v) = self.curr.get(&key) {
+            return v.clone();
+        }
+
+        let v = match self.prev.remove(&key) {
+            Some(v) => v,
+            None => f(),
+        };
+        self.curr.insert(key, v.clone());
+        v
+    }
+
+    /// Drops entries not used since the previous call.
+    pub fn next_generation(&mut self) {
+        self.prev = std::mem::take(&mut self.curr);
+    }
+
+    pub fn clear(&mut self) {
+        self.prev.clear();
+        self.curr.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_values_of_previous_generation() {
+        let mut cache = Cache::default();
+        let mut created = 0;
+        let mut get = |cache: &mut Cache<u32, u32>, key| {
+            cache.get_or_insert_with(key, || {
+                created += 1;
+                key * 2
+            })
+        };
+
+        assert_eq!(get(&mut cache, 1), 2);
+        assert_eq!(get(&mut cache, 1), 2);
+        cache.next_generation();
+        assert_eq!(get(&mut cache, 1), 2);
+        assert_eq!(get(&mut cache, 2), 4);
+        assert_eq!(created, 2);
+    }
+
+    #[test]
+    fn drops_unused_values() {
+        let mut cache = Cache::default();
+        cache.get_or_insert_with(1, || 1);
+        cache.get_or_insert_with(2, || 2);
+        cache.next_generation();
+        cache.get_or_insert_with(2, || 2);
+        cache.next_generation();
+
+        assert_eq!(cache.get_or_insert_with(1, || 3), 3);
+        assert_eq!(cache.get_or_insert_with(2, || 3), 2);
+        cache.clear();
+        assert_eq!(cache.get_or_insert_with(2, || 3), 3);
+    }
+}
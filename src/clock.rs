@@ -0,0 +1,222 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Broken down time, weekday and month are zero based.
+pub struct DateTime {
+    year: i64,
+    month: usize,
+    day: u32,
+    yday: u32,
+    weekday: usize,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl DateTime {
+    /// Converts unix timestamp to the time at given UTC offset in minutes.
+    pub fn from_local(timestamp: i64, utc_offset_minutes: i32) -> DateTime {
+        Self::from_timestamp(timestamp + utc_offset_minutes as i64 * 60)
+    }
+
+    /// Converts unix timestamp to the date using proleptic gregorian calendar.
+    pub fn from_timestamp(timestamp: i64) -> DateTime {
+        let days = timestamp.div_euclid(86400);
+        let secs = timestamp.rem_euclid(86400);
+
+        // Days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 2 } else { mp - 10 };
+        let year = yoe + era * 400 + if month < 2 { 1 } else { 0 };
+
+        // 1970-01-01 was thursday
+        let weekday = (days + 3).rem_euclid(7);
+
+        let is_leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let yday = if month < 2 {
+            doy - 306
+        } else {
+            doy + 59 + if is_leap { 1 } else { 0 }
+        };
+
+        DateTime {
+            year,
+            month: month as usize,
+            day: day as u32,
+            yday: yday as u32 + 1,
+            weekday: weekday as usize,
+            hour: (secs / 3600) as u32,
+            minute: (secs % 3600 / 60) as u32,
+            second: (secs % 60) as u32,
+        }
+    }
+
+    /// Formats the date using strftime like pattern.
+    pub fn format(&self, fmt: &str) -> String {
+        let mut res = String::new();
+        let mut iter = fmt.chars();
+
+        while let Some(c) = iter.next() {
+            if c != '%' {
+                res.push(c);
+                continue;
+            }
+
+            let hour12 = match self.hour % 12 {
+                0 => 12,
+                h => h,
+            };
+            match iter.next() {
+                Some('H') => res.push_str(&format!("{:02}", self.hour)),
+                Some('I') => res.push_str(&format!("{:02}", hour12)),
+                Some('M') => res.push_str(&format!("{:02}", self.minute)),
+                Some('S') => res.push_str(&format!("{:02}", self.second)),
+                Some('p') => res.push_str(if self.hour < 12 { "AM" } else { "PM" }),
+                Some('R') => res.push_str(&format!("{:02}:{:02}", self.hour, self.minute)),
+                Some('T') => res.push_str(&format!(
+                    "{:02}:{:02}:{:02}",
+                    self.hour, self.minute, self.second
+                )),
+                Some('Y') => res.push_str(&self.year.to_string()),
+                Some('y') => res.push_str(&format!("{:02}", self.year.rem_euclid(100))),
+                Some('m') => res.push_str(&format!("{:02}", self.month + 1)),
+                Some('d') => res.push_str(&format!("{:02}", self.day)),
+                Some('e') => res.push_str(&format!("{:>2}", self.day)),
+                Some('j') => res.push_str(&format!("{:03}", self.yday)),
+                Some('a') => res.push_str(&WEEKDAYS[self.weekday][..3]),
+                Some('A') => res.push_str(WEEKDAYS[self.weekday]),
+                Some('b') => res.push_str(&MONTHS[self.month][..3]),
+                Some('B') => res.push_str(MONTHS[self.month]),
+                Some('%') => res.push('%'),
+                Some(c) => {
+                    res.push('%');
+                    res.push(c);
+                }
+                None => res.push('%'),
+            }
+        }
+
+        res
+    }
+}
+
+/// Returns seconds since unix epoch.
+pub fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Returns how often in seconds given format changes.
+pub fn granularity(fmt: &str) -> u64 {
+    let mut iter = fmt.chars();
+    while let Some(c) = iter.next() {
+        if c == '%' && matches!(iter.next(), Some('S') | Some('T')) {
+            return 1;
+        }
+    }
+    60
+}
+
+/// Returns seconds left from `now` until the next tick of given granularity.
+pub fn seconds_until_tick(now: f64, granularity: u64) -> f64 {
+    let granularity = granularity as f64;
+    granularity - now % granularity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATE_FMT: &str = "%Y-%m-%d %j %a";
+
+    #[test]
+    fn converts_epoch() {
+        let t = DateTime::from_timestamp(0);
+        assert_eq!(
+            t.format("%Y-%m-%d %T %j %A"),
+            "1970-01-01 00:00:00 001 Thursday"
+        );
+    }
+
+    #[test]
+    fn converts_leap_year_days() {
+        let t = DateTime::from_timestamp(1709211845);
+        assert_eq!(t.format(DATE_FMT), "2024-02-29 060 Thu");
+        let t = DateTime::from_timestamp(1735689599);
+        assert_eq!(t.format(DATE_FMT), "2024-12-31 366 Tue");
+    }
+
+    #[test]
+    fn converts_weekday_around_new_year() {
+        let t = DateTime::from_timestamp(1704067199);
+        assert_eq!(t.format(DATE_FMT), "2023-12-31 365 Sun");
+        let t = DateTime::from_timestamp(1704067200);
+        assert_eq!(t.format(DATE_FMT), "2024-01-01 001 Mon");
+    }
+
+    #[test]
+    fn shifts_by_negative_offset() {
+        let t = DateTime::from_local(0, -90);
+        assert_eq!(t.format("%Y-%m-%d %R %a"), "1969-12-31 22:30 Wed");
+    }
+
+    #[test]
+    fn formats_directives() {
+        let t = DateTime::from_timestamp(1709211845);
+        assert_eq!(
+            t.format("%H %I %M %S %p %R %T"),
+            "13 01 04 05 PM 13:04 13:04:05"
+        );
+        assert_eq!(
+            t.format("%Y %y %m %d %e %j %a %A %b %B"),
+            "2024 24 02 29 29 060 Thu Thursday Feb February"
+        );
+
+        let t = DateTime::from_timestamp(0);
+        assert_eq!(t.format("%I %p|%e|"), "12 AM| 1|");
+        assert_eq!(t.format("100%% %Q %"), "100% %Q %");
+    }
+
+    #[test]
+    fn finds_granularity() {
+        assert_eq!(granularity("%H:%M"), 60);
+        assert_eq!(granularity("%H:%M:%S"), 1);
+        assert_eq!(granularity("%T"), 1);
+        assert_eq!(granularity("%%S"), 60);
+
+        assert_eq!(seconds_until_tick(1709211845.5, 60), 54.5);
+        assert_eq!(seconds_until_tick(1709211840.0, 1), 1.0);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use crate::CommandState;
+
+/// External command settings.
+pub struct CommandConfig<'a> {
+    pub name: &'a str,
+    pub command: Vec<&'a str>,
+    /// Seconds between command runs.
+    pub interval: f64,
+    /// Seconds to wait for the command result.
+    pub timeout: f64,
+}
+
+/// Owned command settings, deserialized from plugin configuration.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CommandSettings {
+    pub name: String,
+    pub command: Vec<String>,
+    pub interval: f64,
+    pub timeout: f64,
+}
+
+impl From<&CommandConfig<'_>> for CommandSettings {
+    fn from(c: &CommandConfig) -> CommandSettings {
+        CommandSettings {
+            name: c.name.to_string(),
+            command: c.command.iter().map(|s| s.to_string()).collect(),
+            interval: c.interval,
+            timeout: c.timeout,
+        }
+    }
+}
+
+/// Runs commands on behalf of command segments.
+pub trait Executor {
+    fn execute(&mut self, command: &[String], context: BTreeMap<String, String>);
+}
+
+struct Segment {
+    command: Vec<String>,
+    interval: f64,
+    timeout: f64,
+    /// Id and start time of the run waiting for the result.
+    running: Option<(u64, f64)>,
+    last_run: Option<f64>,
+    /// Last good output, kept when command fails or times out.
+    value: Option<String>,
+    /// None until the first run finishes.
+    state: Option<CommandState>,
+}
+
+/// State of all command segments.
+#[derive(Default)]
+pub struct Commands {
+    segments: HashMap<String, Segment>,
+    last_id: u64,
+}
+
+impl Commands {
+    pub fn new(configs: &[CommandSettings]) -> Commands {
+        let segments = configs.iter().map(|c| {
+            let segment = Segment {
+                command: c.command.clone(),
+                interval: c.interval,
+                timeout: c.timeout,
+                running: None,
+                last_run: None,
+                value: None,
+                state: None,
+            };
+            (c.name.clone(), segment)
+        });
+
+        Commands {
+            segments: segments.collect(),
+            last_id: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Expires timed out runs and starts due commands, returns true if any segment changed.
+    pub fn tick(&mut self, now: f64, executor: &mut impl Executor) -> bool {
+        let mut changed = false;
+
+        for (name, s) in self.segments.iter_mut() {
+            if let Some((_, started)) = s.running {
+                if now - started < s.timeout {
+                    continue;
+                }
+                changed |= s.state != Some(CommandState::Timeout);
+                s.state = Some(CommandState::Timeout);
+                s.running = None;
+            }
+
+            let is_due = match s.last_run {
+                Some(t) => now - t >= s.interval,
+                None => true,
+            };
+            if is_due {
+                self.last_id += 1;
+                let context = BTreeMap::from([
+                    ("name".to_string(), name.to_string()),
+                    ("id".to_string(), self.last_id.to_string()),
+                ]);
+                executor.execute(&s.command, context);
+                s.running = Some((self.last_id, now));
+                s.last_run = Some(now);
+            }
+        }
+
+        changed
+    }
+
+    /// Stores the command result, returns true if segment changed.
+    pub fn on_result(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &[u8],
+        context: &BTreeMap<String, String>,
+    ) -> bool {
+        let segment = context.get("name").and_then(|n| self.segments.get_mut(n));
+        let id = context.get("id").and_then(|id| id.parse::<u64>().ok());
+        let s = match segment {
+            Some(s) => s,
+            None => return false,
+        };
+
+        // ignore results of runs which already timed out
+        match s.running {
+            Some((running_id, _)) if Some(running_id) == id => s.running = None,
+            _ => return false,
+        }
+
+        let (value, state) = match exit_code {
+            Some(0) => {
+                let stdout = String::from_utf8_lossy(stdout);
+                let line = stdout.lines().next().unwrap_or("").trim().to_string();
+                (Some(line), CommandState::Success)
+            }
+            _ => (s.value.clone(), CommandState::Failure),
+        };
+
+        let changed = s.value != value || s.state != Some(state);
+        s.value = value;
+        s.state = Some(state);
+        changed
+    }
+
+    /// Returns last good output and state of the segment, None until the first run finishes.
+    pub fn get(&self, name: &str) -> Option<(&str, CommandState)> {
+        let s = self.segments.get(name)?;
+        Some((s.value.as_deref().unwrap_or(""), s.state?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::FakeHost;
+
+    fn commands() -> Commands {
+        Commands::new(&[CommandSettings::from(&CommandConfig {
+            name: "git",
+            command: vec!["git", "branch", "--show-current"],
+            interval: 10.0,
+            timeout: 2.0,
+        })])
+    }
+
+    #[test]
+    fn runs_commands_on_interval() {
+        let mut commands = commands();
+        let mut host = FakeHost::default();
+
+        commands.tick(0.0, &mut host);
+        let (_, context) = host.executed[0].clone();
+        commands.on_result(Some(0), b"main\n", &context);
+        commands.tick(5.0, &mut host);
+        assert_eq!(host.executed.len(), 1);
+
+        commands.tick(10.0, &mut host);
+        assert_eq!(host.executed.len(), 2);
+        assert_eq!(host.executed[1].0, ["git", "branch", "--show-current"]);
+    }
+
+    #[test]
+    fn keeps_last_good_value_on_failure() {
+        let mut commands = commands();
+        let mut host = FakeHost::default();
+
+        commands.tick(0.0, &mut host);
+        assert_eq!(commands.get("git"), None);
+        let (_, context) = host.executed[0].clone();
+        assert!(commands.on_result(Some(0), b"main\n", &context));
+        assert_eq!(commands.get("git"), Some(("main", CommandState::Success)));
+
+        commands.tick(10.0, &mut host);
+        let (_, context) = host.executed[1].clone();
+        assert!(commands.on_result(Some(128), b"", &context));
+        assert_eq!(commands.get("git"), Some(("main", CommandState::Failure)));
+    }
+
+    #[test]
+    fn ignores_results_after_timeout() {
+        let mut commands = commands();
+        let mut host = FakeHost::default();
+
+        commands.tick(0.0, &mut host);
+        assert!(commands.tick(3.0, &mut host));
+        assert_eq!(commands.get("git"), Some(("", CommandState::Timeout)));
+
+        let (_, context) = host.executed[0].clone();
+        assert!(!commands.on_result(Some(0), b"main\n", &context));
+        assert_eq!(commands.get("git"), Some(("", CommandState::Timeout)));
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use crate::{ParseError, ParseErrorKind};
+
+/// Error of a config setting, describes which setting fails and how it can be fixed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    /// Setting key, e.g. `layout` or `tab_layouts.active`.
+    pub key: String,
+    /// Value of the setting, or the unknown name.
+    pub value: String,
+    /// Chars range of the value where the error is.
+    pub span: (usize, usize),
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl ConfigError {
+    /// Returns error spanning the whole value.
+    pub fn new(key: &str, value: &str, message: &str, hint: Option<String>) -> ConfigError {
+        ConfigError {
+            key: key.to_string(),
+            value: value.to_string(),
+            span: (0, value.chars().count()),
+            message: message.to_string(),
+            hint,
+        }
+    }
+
+    /// Returns error of the layout set by the key.
+    pub fn from_parse(key: &str, e: ParseError) -> ConfigError {
+        let message = e.context.trim_end_matches([':', ' ']);
+        ConfigError {
+            key: key.to_string(),
+            hint: Some(Self::get_parse_hint(e.kind, key)),
+            value: e.layout,
+            span: (e.hl_begin, e.hl_end),
+            message: message.to_string(),
+        }
+    }
+
+    fn get_parse_hint(kind: ParseErrorKind, key: &str) -> String {
+        match kind {
+            ParseErrorKind::UnknownColor => "colors are black, red, green, yellow, blue, \
+                magenta, cyan, white, orange, gray, purple, gold, silver, pink and brown"
+                .to_string(),
+            ParseErrorKind::UnknownStyle => {
+                "styles are default, bold, fg:<color> and bg:<color>".to_string()
+            }
+            ParseErrorKind::UnclosedBracket => {
+                "close styles with ] and long components with }".to_string()
+            }
+            ParseErrorKind::UnexpectedToken => format!("the component is not allowed in {key}"),
+            ParseErrorKind::UnknownComponent => "long components are keys, time, date, \
+                cmd:<name>, var:<key>, pane.title, pane.command, panes.tiled, panes.floating, \
+                panes.hidden, clients, tabs, collapsed.first and collapsed.last"
+                .to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (begin, end) = self.span;
+        write!(
+            f,
+            "{}: {} at {}..{} of {:?}",
+            self.key, self.message, begin, end, self.value
+        )?;
+        if let Some(hint) = &self.hint {
+            write!(f, ", {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Component, Parser, Style};
+
+    #[test]
+    fn describes_parse_errors() {
+        let e = Parser::new("#[fg:blu]#N", vec![Component::Style(Style::Default)])
+            .parse()
+            .unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::UnknownColor);
+        let e = ConfigError::from_parse("tab_layouts.active", e);
+
+        assert_eq!(e.message, "Unknown color");
+        assert_eq!(e.span, (2, 8));
+        assert!(e.hint.unwrap().starts_with("colors are"));
+    }
+
+    #[test]
+    fn formats_log_line() {
+        let e = ConfigError::new("tab_overflow", "sideways", "unknown variant", None);
+        assert_eq!(
+            e.to_string(),
+            r#"tab_overflow: unknown variant at 0..8 of "sideways""#
+        );
+    }
+}
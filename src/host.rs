@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use zellij_tile::prelude::*;
+
+use crate::clock;
+use crate::command::Executor;
+
+/// Calls to zellij made by the plugin.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HostCommand {
+    Subscribe(Vec<EventType>),
+    SetSelectable(bool),
+    RequestPermission(Vec<PermissionType>),
+    SetTimeout(f64),
+    /// Switch to the tab with 1-based index.
+    SwitchTab(u32),
+    SwitchMode(InputMode),
+    NewTab,
+    CloseFocusedTab,
+    NextSwapLayout,
+    PreviousSwapLayout,
+}
+
+/// Environment the plugin runs in, so the plugin can be driven without zellij.
+pub trait Host: Executor {
+    fn run(&mut self, command: HostCommand);
+
+    /// Outputs the rendered bar.
+    fn print(&mut self, output: &str);
+
+    /// Returns seconds since unix epoch.
+    fn now(&self) -> f64;
+}
+
+/// Host calling zellij through zellij-tile.
+#[derive(Default)]
+pub struct ZellijHost;
+
+impl Host for ZellijHost {
+    fn run(&mut self, command: HostCommand) {
+        match command {
+            HostCommand::Subscribe(events) => subscribe(&events),
+            HostCommand::SetSelectable(selectable) => set_selectable(selectable),
+            HostCommand::RequestPermission(permissions) => request_permission(&permissions),
+            HostCommand::SetTimeout(secs) => set_timeout(secs),
+            HostCommand::SwitchTab(index) => switch_tab_to(index),
+            HostCommand::SwitchMode(mode) => switch_to_input_mode(&mode),
+            HostCommand::NewTab => new_tab(),
+            HostCommand::CloseFocusedTab => close_focused_tab(),
+            HostCommand::NextSwapLayout => next_swap_layout(),
+            HostCommand::PreviousSwapLayout => previous_swap_layout(),
+        }
+    }
+
+    fn print(&mut self, output: &str) {
+        print!("{}", output);
+    }
+
+    fn now(&self) -> f64 {
+        clock::unix_now()
+    }
+}
+
+impl Executor for ZellijHost {
+    fn execute(&mut self, command: &[String], context: BTreeMap<String, String>) {
+        let args = command.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        run_command(&args, context);
+    }
+}
+
+/// Host which records calls and keeps the last output, used in tests.
+#[cfg(test)]
+#[derive(Default)]
+pub struct FakeHost {
+    pub commands: Vec<HostCommand>,
+    pub executed: Vec<(Vec<String>, BTreeMap<String, String>)>,
+    pub output: String,
+    /// Seconds since unix epoch, the time doesn't pass by itself.
+    pub now: f64,
+}
+
+#[cfg(test)]
+impl Host for FakeHost {
+    fn run(&mut self, command: HostCommand) {
+        self.commands.push(command);
+    }
+
+    fn print(&mut self, output: &str) {
+        self.output = output.to_string();
+    }
+
+    fn now(&self) -> f64 {
+        self.now
+    }
+}
+
+#[cfg(test)]
+impl Executor for FakeHost {
+    fn execute(&mut self, command: &[String], context: BTreeMap<String, String>) {
+        self.executed.push((command.to_vec(), context));
+    }
+}
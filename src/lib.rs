@@ -0,0 +1,2067 @@
+//! The tbar zellij plugin and its template language.
+//!
+//! [`State`] is the plugin, the `tbar` binary registers it in zellij and `tbar-preview` drives
+//! it natively with synthetic zellij state.
+//!
+//! Templates are parsed with [`Parser`] into [`Component`]s, text and styles are rendered with
+//! [`RenderContext`] into a [`Line`] of styled spans, the rest of components is rendered by the
+//! plugin using the template:
+//!
+//! ```
+//! use tbar::{Component, Line, Parser, RenderContext, Style};
+//! use zellij_tile::prelude::Palette;
+//!
+//! let allowed_specials = vec![Component::Style(Style::Default), Component::Session];
+//! let components = Parser::new("#[fg:green]#S ", allowed_specials).parse().unwrap();
+//!
+//! let palette = Palette::default();
+//! let mut line = Line::default();
+//! RenderContext::new(&palette).render(&mut line, &components, |line, c| match c {
+//!     Component::Session => line.push_text("main", usize::MAX),
+//!     _ => {}
+//! });
+//! assert_eq!(line.text(), "main ");
+//! assert_eq!(line.width(), 5);
+//! assert!(line.to_ansi().ends_with("main \x1b[0m"));
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::cmp::{max, min};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use zellij_tile::prelude::*;
+
+pub mod parser;
+pub mod render;
+
+mod activity;
+mod cache;
+pub mod clock;
+pub mod command;
+mod config;
+pub mod error;
+pub mod host;
+#[cfg(any(test, feature = "preview"))]
+pub mod sample;
+mod scroll;
+pub mod settings;
+#[cfg(test)]
+mod snapshot_tests;
+mod vars;
+use crate::activity::Activity;
+use crate::cache::Cache;
+use crate::clock::DateTime;
+use crate::command::Commands;
+use crate::config::Config;
+use crate::error::ConfigError;
+use crate::host::{Host, HostCommand, ZellijHost};
+use crate::settings::{Named, Settings};
+use crate::vars::Vars;
+
+pub use parser::{Color, Component, ParseError, ParseErrorKind, Parser, Style};
+pub use render::{Line, RenderContext, Span, SpanStyle};
+
+type ModeComponents = HashMap<InputMode, Vec<Component>>;
+type TabComponents = HashMap<TabPartState, Vec<Component>>;
+type SwapComponents = HashMap<SwapLayoutState, Vec<Component>>;
+type KeyHintComponents = HashMap<KeyHintState, Vec<Component>>;
+type CommandComponents = HashMap<CommandState, Vec<Component>>;
+type ClientComponents = HashMap<ClientsState, Vec<Component>>;
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub enum TabPartState {
+    Inactive,
+    Active,
+    InactiveSync,
+    ActiveSync,
+    /// Optional, falls back to sync or plain state when not configured.
+    InactiveFullscreen,
+    ActiveFullscreen,
+    /// Optional, falls back to sync or plain state when not configured.
+    InactiveFloating,
+    ActiveFloating,
+    /// Optional, inactive tab with new output since it was active.
+    Activity,
+    /// Optional, inactive tab with finished command, preferred over activity.
+    Bell,
+    LeftMoreTabs,
+    RightMoreTabs,
+    /// Position of the active tab shown instead of tabs which don't fit.
+    Pager,
+}
+
+/// How the tab bar handles tabs which don't fit.
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabOverflow {
+    /// Collapse tabs from the side having more tabs.
+    #[default]
+    Balanced,
+    /// Collapse tabs from the side taking more columns, so the active tab stays centered.
+    Centered,
+    /// Keep visible tabs while the active one is visible, scroll just enough otherwise.
+    Carousel,
+    /// Shrink tab names, inactive ones first, before collapsing tabs.
+    Shrink,
+    /// Show the pager with the active tab only.
+    Pager,
+}
+
+/// Which tab is activated by click on a collapsed tabs marker.
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollapsedClick {
+    /// The nearest collapsed tab.
+    #[default]
+    Nearest,
+    /// The tab one page of visible tabs away.
+    Page,
+}
+
+/// How scrolling over the bar switches tabs.
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabScroll {
+    Disabled,
+    /// Scroll up switches to the next tab.
+    #[default]
+    UpNext,
+    /// Scroll up switches to the previous tab.
+    UpPrevious,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub enum SwapLayoutState {
+    NonDirty,
+    Dirty,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub enum KeyHintState {
+    Full,
+    Short,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum CommandState {
+    Success,
+    Failure,
+    Timeout,
+}
+
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub enum ClientsState {
+    /// Marker of other client focused on the tab.
+    Tab,
+    /// Number of clients connected to the session.
+    Session,
+}
+
+/// Part of the bar which reacts to clicks.
+#[derive(PartialEq, Eq, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClickTarget {
+    Session,
+    Mode,
+    /// Tab, collapsed tabs marker or pager.
+    Tab,
+    SwapLayout,
+    KeyHints,
+    Clock,
+    /// Command segment with given name.
+    Command(String),
+    /// Var segment with given key.
+    Var(String),
+    PaneTitle,
+    PaneCommand,
+    Clients,
+    /// Summary of config errors and the error overlay, clicks show the errors.
+    #[serde(skip)]
+    Errors,
+}
+
+/// Zellij doesn't report middle clicks to plugins.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseButton {
+    Left,
+    Right,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseAction {
+    /// Switch to the clicked tab, only for tabs.
+    SwitchTab,
+    NextSwapLayout,
+    PreviousSwapLayout,
+    /// Switch to locked mode, or back to normal mode from locked.
+    ToggleLock,
+    NewTab,
+    /// Close the clicked tab, or the active one outside the tab bar.
+    CloseTab,
+    /// Start renaming the clicked tab, or the active one outside the tab bar.
+    RenameTab,
+}
+
+/// Columns taken by a clickable part of the bar, tab is the 1-based index of the tab.
+#[derive(Clone, Debug)]
+struct ClickRegion {
+    start: usize,
+    end: usize,
+    target: ClickTarget,
+    tab: Option<usize>,
+}
+
+/// Number of panes in the tab.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+struct PaneStats {
+    tiled: usize,
+    floating: usize,
+    hidden: usize,
+}
+
+#[derive(Default, Clone)]
+struct RenderedTabPart {
+    index: usize,
+    line: Line,
+    len: usize,
+}
+
+/// Measurements of the last rendered bar shown in debug mode.
+#[derive(Default)]
+struct RenderStats {
+    spacer_lens: Vec<usize>,
+    duration: Duration,
+}
+
+/// State the bar is rendered from, a change of state not used by layouts doesn't cause render.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+enum Input {
+    Session,
+    Mode,
+    Palette,
+    Keybinds,
+    /// Tabs with the active one, the focused pane is looked up in it.
+    Tabs,
+    Panes,
+    Vars,
+}
+
+/// Everything a rendered tab part depends on besides templates, palette and the tab bar style,
+/// which clear the cache when changed.
+#[derive(PartialEq, Eq, Hash)]
+struct TabPartKey {
+    state: TabPartState,
+    index: usize,
+    name: String,
+    stats: PaneStats,
+    clients: Vec<u16>,
+    max_name_len: usize,
+    tabs_len: usize,
+}
+
+/// The plugin, renders the bar from zellij state with templates of the loaded settings.
+#[derive(Default)]
+pub struct State<H = ZellijHost> {
+    host: H,
+
+    layout_components: Vec<Component>,
+    mode_components: ModeComponents,
+    tab_components: TabComponents,
+    swap_components: SwapComponents,
+    key_hint_components: KeyHintComponents,
+    key_hints: Vec<(actions::Action, String)>,
+    utc_offset_minutes: i32,
+    command_components: CommandComponents,
+    client_components: ClientComponents,
+
+    mode_info: ModeInfo,
+    tabs: Vec<TabInfo>,
+    panes: PaneManifest,
+    activity: Activity,
+    tab_overflow: TabOverflow,
+    tab_name_min_len: usize,
+    collapsed_click: CollapsedClick,
+    mouse_actions: Vec<(ClickTarget, MouseButton, MouseAction)>,
+    tab_scroll: TabScroll,
+    tab_scroll_wrap: bool,
+    carousel_first: Cell<usize>,
+    /// Style at the beginning of the tab bar, every tab part starts with it.
+    tab_bar_style: Cell<SpanStyle>,
+    tab_part_cache: RefCell<Cache<TabPartKey, RenderedTabPart>>,
+    active_tab_idx: usize,
+    mouse_click_pos: usize,
+    click_regions: RefCell<Vec<ClickRegion>>,
+    rows: usize,
+    cols: usize,
+    /// Row of the bar in the output, debug lines are above it.
+    bar_row: usize,
+    inputs: HashSet<Input>,
+    /// Output rendered by update, printed by the following render call if cols didn't change.
+    pending_output: Option<String>,
+    last_output: String,
+    clocks: Vec<String>,
+    commands: Commands,
+    commands_allowed: bool,
+    vars: Vars,
+    tick_scheduled: bool,
+    errors: Vec<ConfigError>,
+    /// Index of the config error shown over the bar.
+    error_overlay: Option<usize>,
+    /// Shows internal state above the bar, or in its place if the bar has one row.
+    debug: bool,
+    render_stats: RenderStats,
+}
+
+impl<H: Host + Default> ZellijPlugin for State<H> {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        // defaults are loaded first, so layouts which fail to parse keep the default ones
+        let defaults = Settings::from(Config::default());
+        let (settings, errors) = Settings::from_plugin_config(&configuration);
+        self.load_config(&defaults, Vec::new());
+        self.load_config(&defaults.merge(settings), errors);
+
+        let mut permissions = vec![
+            PermissionType::ReadApplicationState,
+            PermissionType::ChangeApplicationState,
+        ];
+        if !self.commands.is_empty() {
+            permissions.push(PermissionType::RunCommands);
+        }
+
+        // Keep the bar selectable until permissions are granted, so the prompt can be answered.
+        self.host.run(HostCommand::SetSelectable(true));
+        self.host.run(HostCommand::RequestPermission(permissions));
+        self.host.run(HostCommand::Subscribe(vec![
+            EventType::ModeUpdate,
+            EventType::TabUpdate,
+            EventType::PaneUpdate,
+            EventType::Mouse,
+            EventType::Timer,
+            EventType::PermissionRequestResult,
+            EventType::RunCommandResult,
+            EventType::CustomMessage,
+        ]));
+        self.schedule_tick();
+    }
+
+    fn update(&mut self, event: Event) -> bool {
+        let mut should_render = false;
+        match event {
+            Event::ModeUpdate(mode_info) => {
+                should_render = self.is_mode_update_used(&mode_info);
+                if self.mode_info.style.colors != mode_info.style.colors {
+                    self.tab_part_cache.get_mut().clear();
+                }
+                self.mode_info = mode_info
+            }
+            Event::TabUpdate(tabs) => {
+                if let Some(active_tab_index) = tabs.iter().position(|t| t.active) {
+                    let active_tab_idx = active_tab_index;
+                    if self.active_tab_idx != active_tab_idx || self.tabs != tabs {
+                        should_render = self.inputs.contains(&Input::Tabs);
+                    }
+                    let activity_changed = self.activity.on_tab_update(active_tab_idx, tabs.len());
+                    should_render |= activity_changed && self.inputs.contains(&Input::Tabs);
+                    self.active_tab_idx = active_tab_idx;
+                    self.tabs = tabs;
+                } else {
+                    eprintln!("Could not find active tab.");
+                }
+            }
+            Event::PaneUpdate(panes) => {
+                if self.panes != panes {
+                    should_render = self.inputs.contains(&Input::Panes);
+                }
+                let activity_changed = self.activity.on_pane_update(&panes, self.active_tab_idx);
+                should_render |= activity_changed && self.inputs.contains(&Input::Tabs);
+                self.panes = panes;
+            }
+            Event::Mouse(me) => match me {
+                Mouse::LeftClick(row, col) => {
+                    should_render = self.on_click(MouseButton::Left, row, col) || self.debug
+                }
+                Mouse::RightClick(row, col) => {
+                    should_render = self.on_click(MouseButton::Right, row, col) || self.debug
+                }
+                Mouse::ScrollUp(_) => self.on_scroll(-1),
+                Mouse::ScrollDown(_) => self.on_scroll(1),
+                _ => {}
+            },
+            Event::Timer(_) => {
+                self.tick_scheduled = false;
+                should_render |=
+                    self.vars.expire(self.host.now()) && self.inputs.contains(&Input::Vars);
+                let clocks = self.get_clocks();
+                if self.clocks != clocks {
+                    should_render = true;
+                }
+                self.clocks = clocks;
+                if self.commands_allowed {
+                    should_render |= self.commands.tick(self.host.now(), &mut self.host);
+                }
+                self.schedule_tick();
+            }
+            Event::PermissionRequestResult(status) => {
+                self.host.run(HostCommand::SetSelectable(false));
+                if let PermissionStatus::Granted = status {
+                    self.commands_allowed = true;
+                    should_render |= self.commands.tick(self.host.now(), &mut self.host);
+                }
+            }
+            Event::RunCommandResult(exit_code, stdout, _stderr, context) => {
+                should_render = self.commands.on_result(exit_code, &stdout, &context);
+            }
+            Event::CustomMessage(name, payload) if name == "tbar" => {
+                should_render = self.apply_message(&payload);
+            }
+            _ => {
+                eprintln!("Got unrecognized event: {:?}", event);
+            }
+        };
+        should_render && self.is_output_changed()
+    }
+
+    fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
+        let should_render = match (pipe_message.name.as_str(), pipe_message.payload) {
+            ("tbar", Some(payload)) => self.apply_message(&payload),
+            _ => false,
+        };
+        should_render && self.is_output_changed()
+    }
+
+    fn render(&mut self, rows: usize, cols: usize) {
+        let output = match self.pending_output.take() {
+            Some(output) if rows == self.rows && cols == self.cols => output,
+            _ => self.render_output(rows, cols),
+        };
+        self.host.print(&output);
+        self.last_output = output;
+    }
+}
+
+impl<H: Host> State<H> {
+    /// Returns the host the plugin runs in.
+    pub fn host(&self) -> &H {
+        &self.host
+    }
+
+    /// Returns errors of the loaded config.
+    pub fn errors(&self) -> &[ConfigError] {
+        &self.errors
+    }
+
+    /// Shows the config error with given index over the bar, like clicks on the error summary.
+    pub fn show_error(&mut self, index: usize) {
+        self.error_overlay = Some(index);
+    }
+
+    /// Renders the bar, in debug mode with debug lines above it, or in its place if there is
+    /// only one row.
+    fn render_output(&mut self, rows: usize, cols: usize) -> String {
+        self.rows = rows;
+        self.bar_row = 0;
+        if !self.debug {
+            return self.render_line(cols);
+        }
+
+        let start = Instant::now();
+        let bar = self.render_line(cols);
+        self.render_stats.duration = start.elapsed();
+
+        let mut lines = self.get_debug_lines(cols);
+        lines.truncate(max(rows, 2) - 1);
+        if rows > 1 {
+            self.bar_row = lines.len();
+            lines.push(bar);
+        } else {
+            // the hidden bar can't be clicked
+            self.click_regions.get_mut().clear();
+        }
+        lines.join("\n")
+    }
+
+    /// Returns lines describing the state of the bar and the last render.
+    fn get_debug_lines(&self, cols: usize) -> Vec<String> {
+        let summary = format!(
+            "mode:{:?} tab:{}/{} click:{} spacers:{:?} render:{:?} errors:{}",
+            self.mode_info.mode,
+            self.active_tab_idx + 1,
+            self.tabs.len(),
+            self.mouse_click_pos,
+            self.render_stats.spacer_lens,
+            self.render_stats.duration,
+            self.errors.len(),
+        );
+        let regions = self.click_regions.borrow();
+        let regions = regions.iter().map(|r| match r.tab {
+            Some(tab) => format!(" {:?}#{tab} {}..{}", r.target, r.start, r.end),
+            None => format!(" {:?} {}..{}", r.target, r.start, r.end),
+        });
+        let regions = format!("regions:{}", regions.collect::<String>());
+
+        let ctx = self.render_context();
+        let iter = [summary, regions].into_iter().map(|text| {
+            let mut line = Line::default();
+            ctx.apply_style(&mut line, &Style::Bg(Color::Yellow));
+            ctx.apply_style(&mut line, &Style::Fg(Color::Black));
+            line.push_text(&format!("{text:cols$}"), cols);
+            line.to_ansi()
+        });
+        iter.collect()
+    }
+
+    /// Renders the bar and its click regions.
+    fn render_line(&mut self, cols: usize) -> String {
+        let mut line = Line::default();
+        let mut lens = Vec::new();
+        // spacers are empty spans until widths of other components are known
+        let mut spacer_spans = Vec::new();
+        let mut cols_left = cols;
+        self.cols = cols;
+        self.click_regions.get_mut().clear();
+        // index of the layout component owning each click region
+        let mut region_owners = Vec::new();
+
+        let overlay = self.error_overlay.map(|i| self.get_error_overlay(i));
+        // the summary of errors is in front of the layout, unless errors are shown
+        if overlay.is_none() && !self.errors.is_empty() {
+            self.render_errors(&mut line, cols_left);
+            cols_left = cols_left.saturating_sub(line.width());
+        }
+        let summary_len = line.width();
+
+        let layout = overlay.as_deref().unwrap_or(&self.layout_components);
+        for (i, component) in layout.iter().enumerate() {
+            if let Component::Spacer = component {
+                spacer_spans.push((i, line.spans.len()));
+                line.push_text("", 0);
+                lens.push(0);
+                continue;
+            }
+            let start = line.width();
+            self.render_layout_component(&mut line, component, cols_left);
+            let len = line.width() - start;
+            cols_left = cols_left.saturating_sub(len);
+            lens.push(len);
+
+            let regions = self.click_regions.get_mut();
+            if let Some(target) = Self::get_click_target(component) {
+                let tab = None;
+                regions.push(ClickRegion {
+                    start: 0,
+                    end: len,
+                    target,
+                    tab,
+                });
+            }
+            region_owners.resize(regions.len(), i);
+        }
+
+        if spacer_spans.is_empty() {
+            spacer_spans.push((lens.len(), line.spans.len()));
+            line.push_text("", 0);
+            lens.push(0);
+        }
+        let spacer_len = cols_left.div_ceil(spacer_spans.len());
+        let spacer_lens = &mut self.render_stats.spacer_lens;
+        spacer_lens.clear();
+
+        for (i, span) in spacer_spans {
+            let n = if cols_left > spacer_len {
+                spacer_len
+            } else {
+                cols_left
+            };
+            cols_left = cols_left.saturating_sub(spacer_len);
+            line.spans[span].text = " ".repeat(n);
+            lens[i] = n;
+            spacer_lens.push(n);
+        }
+
+        // regions are relative to their components until spacers are rendered
+        let starts = lens.iter().scan(summary_len, |start, len| {
+            *start += len;
+            Some(*start - len)
+        });
+        let starts = starts.collect::<Vec<_>>();
+        let regions = self.click_regions.get_mut();
+        for (r, owner) in regions.iter_mut().zip(region_owners) {
+            r.start += starts[owner];
+            r.end += starts[owner];
+        }
+        if summary_len > 0 {
+            let region = ClickRegion {
+                start: 0,
+                end: summary_len,
+                target: ClickTarget::Errors,
+                tab: None,
+            };
+            regions.insert(0, region);
+        }
+        if overlay.is_some() {
+            regions.push(ClickRegion {
+                start: 0,
+                end: cols,
+                target: ClickTarget::Errors,
+                tab: None,
+            });
+        }
+
+        line.to_ansi()
+    }
+
+    /// Renders the bar ahead of the render call, returns false if the output is the same as the
+    /// printed one. Zellij replaces the bar with output of every render call, so identical output
+    /// is suppressed by not requesting the render.
+    fn is_output_changed(&mut self) -> bool {
+        // cols are unknown until the first render
+        if self.cols == 0 {
+            return true;
+        }
+
+        let output = self.render_output(self.rows, self.cols);
+        let changed = output != self.last_output;
+        self.pending_output = Some(output);
+        changed
+    }
+
+    /// Returns true if the mode update changes state used by layouts.
+    fn is_mode_update_used(&self, mode_info: &ModeInfo) -> bool {
+        let prev = &self.mode_info;
+        let changes = [
+            (Input::Session, prev.session_name != mode_info.session_name),
+            (Input::Mode, prev.mode != mode_info.mode),
+            (Input::Palette, prev.style.colors != mode_info.style.colors),
+            (Input::Keybinds, prev.keybinds != mode_info.keybinds),
+        ];
+        let mut iter = changes.into_iter();
+        iter.any(|(input, changed)| changed && self.inputs.contains(&input))
+    }
+
+    /// Collects state used by the layout.
+    fn update_inputs(&mut self) {
+        let mut inputs = HashSet::new();
+        self.add_inputs(&self.layout_components, &mut inputs);
+        if !self.errors.is_empty() {
+            inputs.insert(Input::Palette);
+        }
+        if self.debug {
+            inputs.extend([Input::Mode, Input::Palette, Input::Tabs]);
+        }
+        self.inputs = inputs;
+    }
+
+    /// Adds state used to render the components, including templates of nested components.
+    fn add_inputs(&self, components: &[Component], inputs: &mut HashSet<Input>) {
+        for component in components {
+            match component {
+                Component::Style(_) | Component::LayoutHighlight { .. } => {
+                    inputs.insert(Input::Palette);
+                }
+                Component::Session => {
+                    inputs.insert(Input::Session);
+                }
+                Component::Mode => {
+                    inputs.insert(Input::Mode);
+                    for c in self.mode_components.values() {
+                        self.add_inputs(c, inputs);
+                    }
+                }
+                Component::TabBar => {
+                    inputs.extend([Input::Tabs, Input::Panes]);
+                    for c in self.tab_components.values() {
+                        self.add_inputs(c, inputs);
+                    }
+                }
+                Component::SwapLayout => {
+                    inputs.insert(Input::Tabs);
+                    for c in self.swap_components.values() {
+                        self.add_inputs(c, inputs);
+                    }
+                }
+                Component::KeyHints => {
+                    inputs.extend([Input::Mode, Input::Keybinds]);
+                    for c in self.key_hint_components.values() {
+                        self.add_inputs(c, inputs);
+                    }
+                }
+                Component::Command(_) => {
+                    for c in self.command_components.values() {
+                        self.add_inputs(c, inputs);
+                    }
+                }
+                Component::Var(_) => {
+                    inputs.insert(Input::Vars);
+                }
+                Component::PaneTitle | Component::PaneCommand => {
+                    inputs.extend([Input::Tabs, Input::Panes]);
+                }
+                Component::Clients => {
+                    inputs.insert(Input::Tabs);
+                    for c in self.client_components.values() {
+                        self.add_inputs(c, inputs);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    /// Parses layouts and applies the settings, they are expected to be merged over defaults.
+    /// Layouts which fail to parse keep components of the previously loaded config, errors are
+    /// written to the log and shown in the bar together with errors passed by the caller.
+    pub fn load_config(&mut self, cfg: &Settings, mut errors: Vec<ConfigError>) {
+        errors.extend(cfg.validate());
+        self.debug = cfg.debug.unwrap_or_default();
+
+        let layout = cfg.layout.as_deref().unwrap_or("");
+        match Self::parse_layout(layout) {
+            Ok(c) => self.layout_components = c,
+            Err(e) => errors.push(ConfigError::from_parse("layout", e)),
+        }
+
+        Self::parse_layouts(
+            &mut self.mode_components,
+            &cfg.mode_layouts(),
+            |_| vec![Component::Style(Style::Default)],
+            &mut errors,
+        );
+        Self::parse_layouts(
+            &mut self.tab_components,
+            &cfg.tab_layouts(),
+            Self::get_tab_specials,
+            &mut errors,
+        );
+        Self::parse_layouts(
+            &mut self.swap_components,
+            &cfg.swap_layouts(),
+            |_| vec![Component::Style(Style::Default), Component::Name],
+            &mut errors,
+        );
+        Self::parse_layouts(
+            &mut self.key_hint_components,
+            &cfg.key_hint_layouts(),
+            |_| {
+                vec![
+                    Component::Style(Style::Default),
+                    Component::Key,
+                    Component::Name,
+                ]
+            },
+            &mut errors,
+        );
+        Self::parse_layouts(
+            &mut self.command_components,
+            &cfg.command_layouts(),
+            |_| vec![Component::Style(Style::Default), Component::Name],
+            &mut errors,
+        );
+        Self::parse_layouts(
+            &mut self.client_components,
+            &cfg.client_layouts(),
+            |_| vec![Component::Style(Style::Default), Component::Index],
+            &mut errors,
+        );
+
+        // layouts are parsed in random order
+        errors.sort_by(|a, b| a.key.cmp(&b.key));
+        for e in &errors {
+            eprintln!("Error in config: {e}");
+        }
+        self.errors = errors;
+        self.error_overlay = None;
+        if self.debug {
+            self.log_parse_trees();
+        }
+        self.tab_part_cache.get_mut().clear();
+        self.update_inputs();
+
+        self.key_hints = cfg.key_hints();
+        self.utc_offset_minutes = cfg.utc_offset_minutes.unwrap_or_default();
+        self.tab_overflow = cfg.tab_overflow.unwrap_or_default();
+        self.tab_name_min_len = cfg.tab_name_min_len.unwrap_or_default();
+        self.collapsed_click = cfg.collapsed_click.unwrap_or_default();
+        self.mouse_actions = cfg.mouse_actions.clone().unwrap_or_default();
+        self.tab_scroll = cfg.tab_scroll.unwrap_or_default();
+        self.tab_scroll_wrap = cfg.tab_scroll_wrap.unwrap_or_default();
+        self.commands = Commands::new(cfg.commands.as_deref().unwrap_or_default());
+    }
+
+    fn parse_layout(layout: &str) -> Result<Vec<Component>, ParseError> {
+        let allowed_specials = vec![
+            Component::Spacer,
+            Component::Style(Style::Default),
+            Component::Session,
+            Component::Mode,
+            Component::TabBar,
+            Component::SwapLayout,
+            Component::KeyHints,
+            Component::Clock(String::new()),
+            Component::Command(String::new()),
+            Component::Var(String::new()),
+            Component::PaneTitle,
+            Component::PaneCommand,
+            Component::Clients,
+        ];
+        Parser::new(layout, allowed_specials).parse()
+    }
+
+    /// Parses layouts of modes or states, components of layouts which fail to parse are kept.
+    fn parse_layouts<T: Named>(
+        components: &mut HashMap<T, Vec<Component>>,
+        layouts: &HashMap<T, &str>,
+        allowed_specials: impl Fn(T) -> Vec<Component>,
+        errors: &mut Vec<ConfigError>,
+    ) {
+        for (k, v) in layouts {
+            match Parser::new(v, allowed_specials(*k)).parse() {
+                Ok(c) => {
+                    components.insert(*k, c);
+                }
+                Err(e) => errors.push(ConfigError::from_parse(&k.key(), e)),
+            }
+        }
+
+        // missing layouts are reported by validate, they render nothing
+        for (_, state) in T::NAMES.iter().filter(|(_, s)| !T::OPTIONAL.contains(s)) {
+            components.entry(*state).or_default();
+        }
+    }
+
+    /// Writes parsed components of all layouts to the log.
+    fn log_parse_trees(&self) {
+        eprintln!("Parsed layout: {:?}", self.layout_components);
+        Self::log_components(&self.mode_components);
+        Self::log_components(&self.tab_components);
+        Self::log_components(&self.swap_components);
+        Self::log_components(&self.key_hint_components);
+        Self::log_components(&self.command_components);
+        Self::log_components(&self.client_components);
+    }
+
+    fn log_components<T: Named>(components: &HashMap<T, Vec<Component>>) {
+        for (_, state) in T::NAMES {
+            if let Some(c) = components.get(state) {
+                eprintln!("Parsed {}: {:?}", state.key(), c);
+            }
+        }
+    }
+
+    fn get_tab_specials(state: TabPartState) -> Vec<Component> {
+        let mut allowed_specials = vec![
+            Component::Style(Style::Default),
+            Component::Index,
+            Component::TabCount,
+        ];
+        if !matches!(
+            state,
+            TabPartState::LeftMoreTabs | TabPartState::RightMoreTabs | TabPartState::Pager
+        ) {
+            allowed_specials.push(Component::Name);
+            allowed_specials.push(Component::TiledPanes);
+            allowed_specials.push(Component::FloatingPanes);
+            allowed_specials.push(Component::HiddenPanes);
+            allowed_specials.push(Component::Clients);
+        } else if !matches!(state, TabPartState::Pager) {
+            allowed_specials.push(Component::CollapsedFirst);
+            allowed_specials.push(Component::CollapsedLast);
+        }
+        allowed_specials
+    }
+
+    /// Returns components describing the config error, they replace the layout while the error
+    /// overlay is shown.
+    fn get_error_overlay(&self, index: usize) -> Vec<Component> {
+        let e = &self.errors[index];
+        let (hl_begin, hl_end) = e.span;
+        let position = format!("{}/{}", index + 1, self.errors.len());
+        let mut res = vec![
+            Component::Style(Style::Bg(Color::Red)),
+            Component::Style(Style::Fg(Color::Black)),
+            Component::Text(format!(" {position} {}: {} ", e.key, e.message)),
+        ];
+        if hl_begin < hl_end {
+            res.push(Component::LayoutHighlight {
+                layout: e.value.clone(),
+                hl_begin,
+                hl_end,
+            });
+        }
+        if let Some(hint) = &e.hint {
+            res.push(Component::Text(format!(" {hint}")));
+        }
+        res.push(Component::Spacer);
+        res
+    }
+
+    fn render_context(&self) -> RenderContext<'_> {
+        RenderContext::new(&self.mode_info.style.colors)
+    }
+
+    /// Renders the number of config errors, the part is dropped if it doesn't fit.
+    fn render_errors(&self, line: &mut Line, cols_left: usize) {
+        let ctx = self.render_context();
+        let mut part = line.fork();
+        ctx.apply_style(&mut part, &Style::Bg(Color::Red));
+        ctx.apply_style(&mut part, &Style::Fg(Color::Black));
+        part.push_text(&format!(" ✗ {} ", self.errors.len()), usize::MAX);
+        ctx.apply_style(&mut part, &Style::Default);
+
+        if part.width() <= cols_left {
+            line.append(part);
+        }
+    }
+
+    fn render_session(&self, line: &mut Line, cols_left: usize) {
+        let name = self.mode_info.session_name.as_deref();
+        line.push_text(name.unwrap_or(""), cols_left);
+    }
+
+    fn render_mode(&self, line: &mut Line, cols_left: usize) {
+        let components = &self.mode_components[&self.mode_info.mode];
+        let mut part = line.fork();
+        self.render_context()
+            .render(&mut part, components, |part, c| {
+                self.render_layout_component(part, c, cols_left)
+            });
+
+        if part.width() <= cols_left {
+            line.append(part);
+        }
+    }
+
+    fn render_tab_part(
+        &self,
+        tab_part_state: TabPartState,
+        index: usize,
+        name: &str,
+        stats: PaneStats,
+        clients: &[u16],
+        max_name_len: usize,
+    ) -> RenderedTabPart {
+        let key = TabPartKey {
+            state: tab_part_state,
+            index,
+            name: name.to_string(),
+            stats,
+            clients: clients.to_vec(),
+            max_name_len,
+            tabs_len: self.tabs.len(),
+        };
+        self.tab_part_cache
+            .borrow_mut()
+            .get_or_insert_with(key, || {
+                self.render_tab_part_uncached(
+                    tab_part_state,
+                    index,
+                    name,
+                    stats,
+                    clients,
+                    max_name_len,
+                )
+            })
+    }
+
+    fn render_tab_part_uncached(
+        &self,
+        tab_part_state: TabPartState,
+        index: usize,
+        name: &str,
+        stats: PaneStats,
+        clients: &[u16],
+        max_name_len: usize,
+    ) -> RenderedTabPart {
+        let render_tab_name = self.get_tab_name(tab_part_state, name);
+        let render_tab_name = Self::truncate_name(render_tab_name, max_name_len);
+        let components = &self.tab_components[&tab_part_state];
+        let mut line = Line::new(self.tab_bar_style.get());
+        self.render_context()
+            .render(&mut line, components, |line, c| match c {
+                Component::Index => line.push_text(&index.to_string(), usize::MAX),
+                Component::Name => line.push_text(&render_tab_name, usize::MAX),
+                Component::TabCount => line.push_text(&self.tabs.len().to_string(), usize::MAX),
+                Component::TiledPanes => line.push_text(&stats.tiled.to_string(), usize::MAX),
+                Component::FloatingPanes => line.push_text(&stats.floating.to_string(), usize::MAX),
+                Component::HiddenPanes => line.push_text(&stats.hidden.to_string(), usize::MAX),
+                Component::Clients => self.render_tab_clients(line, clients),
+                Component::CollapsedFirst => {
+                    let (first, _) = self.get_collapsed_range(tab_part_state, index);
+                    line.push_text(&first.to_string(), usize::MAX)
+                }
+                Component::CollapsedLast => {
+                    let (_, last) = self.get_collapsed_range(tab_part_state, index);
+                    line.push_text(&last.to_string(), usize::MAX)
+                }
+                _ => line.push_text("{unparsed}", usize::MAX),
+            });
+
+        let len = line.width();
+        RenderedTabPart { index, line, len }
+    }
+
+    /// Returns 1-based indices of the first and the last tabs hidden behind the marker.
+    fn get_collapsed_range(&self, tab_part_state: TabPartState, count: usize) -> (usize, usize) {
+        match tab_part_state {
+            TabPartState::RightMoreTabs => (self.tabs.len() + 1 - count, self.tabs.len()),
+            _ => (1, count),
+        }
+    }
+
+    fn get_tab_name<'b>(&self, tab_part_state: TabPartState, name: &'b str) -> &'b str {
+        if name.is_empty() {
+            "Enter name..."
+        } else if self.tab_components[&tab_part_state]
+            .iter()
+            .any(|x| matches!(x, Component::Index))
+            && name.contains("Tab #")
+        {
+            "Tab"
+        } else {
+            name
+        }
+    }
+
+    /// Truncates name to max_name_len chars ending with ellipsis.
+    fn truncate_name(name: &str, max_name_len: usize) -> String {
+        if name.chars().count() <= max_name_len {
+            return name.to_string();
+        }
+
+        let mut res = name
+            .chars()
+            .take(max_name_len.saturating_sub(1))
+            .collect::<String>();
+        res.push('…');
+        res
+    }
+
+    /// Renders the marker for each of other clients focused on the tab.
+    fn render_tab_clients(&self, line: &mut Line, clients: &[u16]) {
+        for client_id in clients {
+            let components = &self.client_components[&ClientsState::Tab];
+            self.render_context()
+                .render(line, components, |line, c| match c {
+                    Component::Index => line.push_text(&client_id.to_string(), usize::MAX),
+                    _ => line.push_text("{unparsed}", usize::MAX),
+                });
+        }
+    }
+
+    /// Renders number of clients in the session, each client is focused on exactly one tab.
+    fn render_session_clients(&self, line: &mut Line, cols_left: usize) {
+        let others: usize = self
+            .tabs
+            .iter()
+            .map(|t| t.other_focused_clients.len())
+            .sum();
+        if others == 0 {
+            return;
+        }
+
+        let components = &self.client_components[&ClientsState::Session];
+        let mut part = line.fork();
+        self.render_context()
+            .render(&mut part, components, |part, c| match c {
+                Component::Index => part.push_text(&(others + 1).to_string(), usize::MAX),
+                _ => part.push_text("{unparsed}", usize::MAX),
+            });
+
+        if part.width() <= cols_left {
+            line.append(part);
+        }
+    }
+
+    /// Counts selectable panes of the tab, so plugin bars are not counted.
+    fn get_pane_stats(&self, tab: &TabInfo) -> PaneStats {
+        let mut stats = PaneStats {
+            hidden: tab.panes_to_hide,
+            ..Default::default()
+        };
+
+        let panes = self.panes.panes.get(&tab.position).into_iter().flatten();
+        for p in panes.filter(|p| p.is_selectable) {
+            match (p.is_suppressed, p.is_floating) {
+                (true, _) => stats.hidden += 1,
+                (false, true) => stats.floating += 1,
+                (false, false) => stats.tiled += 1,
+            }
+        }
+
+        stats
+    }
+
+    /// Returns the most specific configured state of the tab, bell is preferred over activity,
+    /// activity over fullscreen, fullscreen over floating and floating over sync.
+    fn get_tab_part_state(&self, tab: &TabInfo) -> TabPartState {
+        use TabPartState::*;
+        let (fullscreen, floating, sync, plain) = match tab.active {
+            true => (ActiveFullscreen, ActiveFloating, ActiveSync, Active),
+            false => (InactiveFullscreen, InactiveFloating, InactiveSync, Inactive),
+        };
+
+        let candidates = [
+            (!tab.active && self.activity.has_bell(tab.position), Bell),
+            (
+                !tab.active && self.activity.has_activity(tab.position),
+                Activity,
+            ),
+            (tab.is_fullscreen_active, fullscreen),
+            (tab.are_floating_panes_visible, floating),
+            (tab.is_sync_panes_active, sync),
+        ];
+        let mut iter = candidates.into_iter();
+        match iter.find(|(is_set, s)| *is_set && self.tab_components.contains_key(s)) {
+            Some((_, s)) => s,
+            None => plain,
+        }
+    }
+
+    /// Returns parts of all tabs, names are truncated to max_name_len of the tab position.
+    fn get_tab_parts(&self, max_name_len: impl Fn(usize) -> usize) -> Vec<RenderedTabPart> {
+        let mut res = Vec::new();
+
+        for (i, t) in self.tabs.iter().enumerate() {
+            let layout_key = self.get_tab_part_state(t);
+            let stats = self.get_pane_stats(t);
+            let clients = &t.other_focused_clients;
+            res.push(self.render_tab_part(
+                layout_key,
+                i + 1,
+                &t.name,
+                stats,
+                clients,
+                max_name_len(i),
+            ));
+        }
+
+        res
+    }
+
+    /// Adds click regions of tab parts relative to the tab bar.
+    fn add_tab_click_regions(&self, tab_parts: &[RenderedTabPart]) {
+        let mut regions = self.click_regions.borrow_mut();
+        let mut start = 0;
+        for part in tab_parts {
+            regions.push(ClickRegion {
+                start,
+                end: start + part.len,
+                target: ClickTarget::Tab,
+                tab: Some(part.index),
+            });
+            start += part.len;
+        }
+    }
+
+    /// Returns collapsed tabs markers shown around the range of tabs.
+    fn get_collapsed_markers(
+        &self,
+        tabs_len: usize,
+        first: usize,
+        last: usize,
+    ) -> (Option<RenderedTabPart>, Option<RenderedTabPart>) {
+        let empty = PaneStats::default();
+        let page = match self.collapsed_click {
+            CollapsedClick::Nearest => 1,
+            CollapsedClick::Page => last - first + 1,
+        };
+
+        // marker index is the 1-based index of the tab activated by click on it
+        let left = (first > 0).then(|| {
+            let mut part =
+                self.render_tab_part(TabPartState::LeftMoreTabs, first, "", empty, &[], 0);
+            part.index = first.saturating_sub(page) + 1;
+            part
+        });
+
+        let right_count = tabs_len - 1 - last;
+        let right = (right_count > 0).then(|| {
+            let state = TabPartState::RightMoreTabs;
+            let mut part = self.render_tab_part(state, right_count, "", empty, &[], 0);
+            part.index = min(last + page, tabs_len - 1) + 1;
+            part
+        });
+
+        (left, right)
+    }
+
+    /// Returns visible tab parts in range surrounded by collapsed tabs markers.
+    fn collapse_tab_parts(
+        &self,
+        tab_parts: &[RenderedTabPart],
+        first: usize,
+        last: usize,
+    ) -> Vec<RenderedTabPart> {
+        let (left, right) = self.get_collapsed_markers(tab_parts.len(), first, last);
+        let mut res = Vec::from_iter(left);
+        res.extend_from_slice(&tab_parts[first..=last]);
+        res.extend(right);
+        res
+    }
+
+    /// Returns offsets of tab parts in the uncollapsed tab bar, followed by its length.
+    fn get_tab_offsets(tab_parts: &[RenderedTabPart]) -> Vec<usize> {
+        let mut res = vec![0];
+        res.extend(tab_parts.iter().scan(0, |offset, x| {
+            *offset += x.len;
+            Some(*offset)
+        }));
+        res
+    }
+
+    /// Returns length of the range of tabs with collapsed tabs markers without building it.
+    fn get_collapsed_len(&self, offsets: &[usize], first: usize, last: usize) -> usize {
+        let (left, right) = self.get_collapsed_markers(offsets.len() - 1, first, last);
+        let markers_len: usize = left.iter().chain(&right).map(|x| x.len).sum();
+        offsets[last + 1] - offsets[first] + markers_len
+    }
+
+    /// Returns range of tabs which fits cols_left with collapsed tabs markers, removing tabs from
+    /// the side having more tabs, or more columns if the active tab should be centered.
+    fn get_balanced_range(
+        &self,
+        tab_parts: &[RenderedTabPart],
+        cols_left: usize,
+    ) -> (usize, usize) {
+        let active = self.active_tab_idx;
+        let offsets = Self::get_tab_offsets(tab_parts);
+        let (mut first, mut last) = (0, tab_parts.len() - 1);
+
+        while self.get_collapsed_len(&offsets, first, last) > cols_left {
+            let before = active - first;
+            let after = last - active;
+            let remove_left = match self.tab_overflow {
+                TabOverflow::Centered => {
+                    let left = offsets[active] - offsets[first];
+                    let right = offsets[last + 1] - offsets[active + 1];
+                    before != 0 && (after == 0 || left >= right)
+                }
+                _ => before != 0 && before >= after,
+            };
+
+            if remove_left {
+                first += 1;
+            } else if after != 0 {
+                last -= 1;
+            } else {
+                break;
+            }
+        }
+
+        (first, last)
+    }
+
+    /// Returns range of tabs which keeps the previous first visible tab while the active one is
+    /// visible, otherwise the range is scrolled just enough to show it.
+    fn get_carousel_range(
+        &self,
+        tab_parts: &[RenderedTabPart],
+        cols_left: usize,
+    ) -> (usize, usize) {
+        let active = self.active_tab_idx;
+        let offsets = Self::get_tab_offsets(tab_parts);
+        let fits = |first, last| self.get_collapsed_len(&offsets, first, last) <= cols_left;
+
+        // the range only moves right while scrolling to the active tab, so it takes linear time
+        let mut first = min(self.carousel_first.get(), active);
+        let mut last = first;
+        loop {
+            while last + 1 < tab_parts.len() && fits(first, last + 1) {
+                last += 1;
+            }
+            if last >= active || first == active {
+                last = max(last, active);
+                break;
+            }
+            first += 1;
+            // the left marker shown after scrolling from the first tab may take more columns
+            last = max(last, first);
+            while last > first && !fits(first, last) {
+                last -= 1;
+            }
+        }
+
+        // fill the space left after scrolling to the end
+        while first > 0 && fits(first - 1, last) {
+            first -= 1;
+        }
+        while last > active && !fits(first, last) {
+            last -= 1;
+        }
+
+        self.carousel_first.set(first);
+        (first, last)
+    }
+
+    /// Shrinks names of inactive tabs proportionally to their lengths, down to
+    /// tab_name_min_len.
+    fn get_shrunk_tab_parts(&self, cols_left: usize) -> Vec<RenderedTabPart> {
+        let tab_parts = self.get_tab_parts(|_| usize::MAX);
+        let len = tab_parts.iter().map(|x| x.len).sum::<usize>();
+        if len <= cols_left {
+            return tab_parts;
+        }
+
+        let min_len = max(self.tab_name_min_len, 1);
+        let name_lens = self.tabs.iter().map(|t| {
+            let name = self.get_tab_name(self.get_tab_part_state(t), &t.name);
+            (!t.active).then(|| name.chars().count())
+        });
+        let name_lens = name_lens.collect::<Vec<_>>();
+        // names are shrunk to the ratio of their length in per mille, ellipsis included
+        let shrunk_len =
+            |name_len: usize, ratio: usize| min(name_len, max(name_len * ratio / 1000, min_len));
+        let saved_len = |ratio| {
+            let name_lens = name_lens.iter().flatten();
+            name_lens.map(|l| l - shrunk_len(*l, ratio)).sum::<usize>()
+        };
+
+        // the largest ratio which fits, names are shrunk the most if none fits
+        let (mut ratio, mut max_ratio) = (0, 1000_usize);
+        while ratio < max_ratio {
+            let mid = (ratio + max_ratio).div_ceil(2);
+            if len - saved_len(mid) <= cols_left {
+                ratio = mid;
+            } else {
+                max_ratio = mid - 1;
+            }
+        }
+
+        self.get_tab_parts(|i| match name_lens[i] {
+            Some(name_len) => shrunk_len(name_len, ratio),
+            None => usize::MAX,
+        })
+    }
+
+    /// Returns the active tab part, its name is shrunk to fit cols_left if tab names are shrunk.
+    fn get_active_tab_part(
+        &self,
+        tab_parts: &[RenderedTabPart],
+        cols_left: usize,
+    ) -> RenderedTabPart {
+        let part = tab_parts[self.active_tab_idx].clone();
+        let tab = match self.tabs.get(self.active_tab_idx) {
+            Some(tab) if self.tab_overflow == TabOverflow::Shrink && part.len > cols_left => tab,
+            _ => return part,
+        };
+
+        let state = self.get_tab_part_state(tab);
+        let name_len = self.get_tab_name(state, &tab.name).chars().count();
+        let min_len = max(self.tab_name_min_len, 1);
+        let stats = self.get_pane_stats(tab);
+        let clients = &tab.other_focused_clients;
+        let index = self.active_tab_idx + 1;
+
+        // shrinking the name by one char makes the part one char shorter
+        let max_name_len = max(name_len.saturating_sub(part.len - cols_left), min_len);
+        self.render_tab_part(state, index, &tab.name, stats, clients, max_name_len)
+    }
+
+    /// Returns pager with the active tab if all tabs don't fit.
+    fn get_pager_tab_parts(
+        &self,
+        tab_parts: Vec<RenderedTabPart>,
+        cols_left: usize,
+    ) -> Vec<RenderedTabPart> {
+        if tab_parts.iter().map(|x| x.len).sum::<usize>() <= cols_left {
+            return tab_parts;
+        }
+
+        let index = self.active_tab_idx + 1;
+        let state = TabPartState::Pager;
+        let pager = self.render_tab_part(state, index, "", PaneStats::default(), &[], 0);
+        let active = tab_parts[self.active_tab_idx].clone();
+
+        if pager.len + active.len <= cols_left {
+            vec![pager, active]
+        } else {
+            vec![pager]
+        }
+    }
+
+    fn render_tab_bar(&self, line: &mut Line, cols_left: usize) {
+        let mut cache = self.tab_part_cache.borrow_mut();
+        if self.tab_bar_style.replace(line.style) != line.style {
+            cache.clear();
+        }
+        cache.next_generation();
+        drop(cache);
+
+        let tab_parts = match self.tab_overflow {
+            TabOverflow::Shrink => self.get_shrunk_tab_parts(cols_left),
+            _ => self.get_tab_parts(|_| usize::MAX),
+        };
+
+        let parts = if tab_parts.is_empty() {
+            tab_parts
+        } else if let TabOverflow::Pager = self.tab_overflow {
+            self.get_pager_tab_parts(tab_parts, cols_left)
+        } else {
+            let (first, last) = match self.tab_overflow {
+                TabOverflow::Carousel => self.get_carousel_range(&tab_parts, cols_left),
+                _ => self.get_balanced_range(&tab_parts, cols_left),
+            };
+            let parts = self.collapse_tab_parts(&tab_parts, first, last);
+
+            // drop collapsed tabs markers if only the active tab fits
+            if parts.iter().map(|x| x.len).sum::<usize>() > cols_left {
+                vec![self.get_active_tab_part(&tab_parts, cols_left)]
+            } else {
+                parts
+            }
+        };
+
+        // render nothing if cols_left is less than an active tab length
+        if parts.iter().map(|x| x.len).sum::<usize>() > cols_left {
+            return;
+        }
+
+        self.add_tab_click_regions(&parts);
+
+        for part in parts {
+            line.append(part.line);
+        }
+    }
+
+    fn render_swap_layout_part(&self, line: &mut Line, name: &str, is_dirty: bool) {
+        let key = match is_dirty {
+            true => SwapLayoutState::Dirty,
+            false => SwapLayoutState::NonDirty,
+        };
+
+        self.render_context()
+            .render(line, &self.swap_components[&key], |line, c| match c {
+                Component::Name => line.push_text(name, usize::MAX),
+                _ => line.push_text("{unparsed}", usize::MAX),
+            })
+    }
+
+    fn render_swap_layout(&self, line: &mut Line, cols_left: usize) {
+        if let Some(active_tab) = self.tabs.get(self.active_tab_idx) {
+            let mut part = line.fork();
+            if let Some(n) = &active_tab.active_swap_layout_name {
+                self.render_swap_layout_part(&mut part, n, active_tab.is_swap_layout_dirty);
+            }
+
+            if part.width() <= cols_left {
+                line.append(part);
+            }
+        }
+    }
+
+    /// Returns (key, label) pairs of configured hints bound in the current mode.
+    fn get_key_hints(&self) -> Vec<(String, &str)> {
+        let mut res = Vec::new();
+        let keybinds = &self.mode_info.keybinds;
+        let binds = match keybinds.iter().find(|(m, _)| *m == self.mode_info.mode) {
+            Some((_, binds)) => binds,
+            None => return res,
+        };
+
+        for (action, label) in &self.key_hints {
+            let bound_key = binds.iter().find(|(_, actions)| actions.contains(action));
+            if let Some((key, _)) = bound_key {
+                res.push((key.to_string(), label.as_str()));
+            }
+        }
+
+        res
+    }
+
+    fn render_key_hint_part(&self, line: &mut Line, state: KeyHintState, key: &str, label: &str) {
+        self.render_context()
+            .render(line, &self.key_hint_components[&state], |line, c| match c {
+                Component::Key => line.push_text(key, usize::MAX),
+                Component::Name => line.push_text(label, usize::MAX),
+                _ => line.push_text("{unparsed}", usize::MAX),
+            })
+    }
+
+    fn render_key_hints(&self, line: &mut Line, cols_left: usize) {
+        let hints = self.get_key_hints();
+
+        for state in [KeyHintState::Full, KeyHintState::Short] {
+            let mut part = line.fork();
+            for (key, label) in &hints {
+                self.render_key_hint_part(&mut part, state, key, label);
+            }
+
+            if part.width() <= cols_left {
+                line.append(part);
+                return;
+            }
+        }
+    }
+
+    /// Returns formatted text of every clock in the layout.
+    fn get_clocks(&self) -> Vec<String> {
+        let now = DateTime::from_local(self.host.now() as i64, self.utc_offset_minutes);
+        let iter = self.layout_components.iter();
+        iter.filter_map(|c| match c {
+            Component::Clock(fmt) => Some(now.format(fmt)),
+            _ => None,
+        })
+        .collect()
+    }
+
+    /// Applies the plugin message, lines `debug`, `debug on` and `debug off` toggle debug mode,
+    /// other lines set vars.
+    fn apply_message(&mut self, message: &str) -> bool {
+        let (debug_lines, var_lines): (Vec<_>, Vec<_>) = message
+            .lines()
+            .partition(|l| l.split_whitespace().next() == Some("debug"));
+
+        let mut changed = false;
+        for line in debug_lines {
+            let debug = match line.split_whitespace().nth(1) {
+                None => !self.debug,
+                Some("on") => true,
+                Some("off") => false,
+                Some(_) => {
+                    eprintln!("Could not apply message: Unknown debug argument: {line}");
+                    continue;
+                }
+            };
+            changed |= self.set_debug(debug);
+        }
+
+        match self.vars.apply(&var_lines.join("\n"), self.host.now()) {
+            Ok(vars_changed) => {
+                self.schedule_tick();
+                changed || vars_changed
+            }
+            Err(e) => {
+                eprintln!("Could not apply message: {}", e);
+                changed
+            }
+        }
+    }
+
+    /// Returns true if debug mode changes, parse trees are logged when it's turned on.
+    fn set_debug(&mut self, debug: bool) -> bool {
+        if self.debug == debug {
+            return false;
+        }
+
+        self.debug = debug;
+        if debug {
+            self.log_parse_trees();
+        }
+        self.update_inputs();
+        true
+    }
+
+    /// Schedules the timer to the next tick of the most frequently changing clock, commands and
+    /// expiring vars are checked every second.
+    fn schedule_tick(&mut self) {
+        if self.tick_scheduled {
+            return;
+        }
+
+        let iter = self.layout_components.iter();
+        let granularity = iter
+            .filter_map(|c| match c {
+                Component::Clock(fmt) => Some(clock::granularity(fmt)),
+                _ => None,
+            })
+            .chain((!self.commands.is_empty() || self.vars.has_expiring()).then_some(1))
+            .min();
+
+        if let Some(g) = granularity {
+            self.host
+                .run(HostCommand::SetTimeout(clock::seconds_until_tick(
+                    self.host.now(),
+                    g,
+                )));
+            self.tick_scheduled = true;
+        }
+    }
+
+    fn render_clock(&self, line: &mut Line, fmt: &str, cols_left: usize) {
+        let now = DateTime::from_local(self.host.now() as i64, self.utc_offset_minutes);
+        line.push_text(&now.format(fmt), cols_left)
+    }
+
+    fn render_command(&self, line: &mut Line, name: &str, cols_left: usize) {
+        let (value, state) = match self.commands.get(name) {
+            Some(c) => c,
+            None => return,
+        };
+
+        let mut part = line.fork();
+        self.render_context().render(
+            &mut part,
+            &self.command_components[&state],
+            |part, c| match c {
+                Component::Name => part.push_text(value, usize::MAX),
+                _ => part.push_text("{unparsed}", usize::MAX),
+            },
+        );
+
+        if part.width() <= cols_left {
+            line.append(part);
+        }
+    }
+
+    fn render_var(&self, line: &mut Line, key: &str, cols_left: usize) {
+        line.push_text(self.vars.get(key).unwrap_or(""), cols_left)
+    }
+
+    /// Returns focused pane of the active tab, floating one if floating panes are visible.
+    fn get_focused_pane(&self) -> Option<&PaneInfo> {
+        let tab = self.tabs.get(self.active_tab_idx)?;
+        let panes = self.panes.panes.get(&tab.position)?;
+        let mut focused = panes.iter().filter(|p| p.is_focused && !p.is_suppressed);
+
+        if tab.are_floating_panes_visible {
+            focused.find(|p| p.is_floating)
+        } else {
+            focused.find(|p| !p.is_floating)
+        }
+    }
+
+    fn render_pane_title(&self, line: &mut Line, cols_left: usize) {
+        if let Some(pane) = self.get_focused_pane() {
+            line.push_text(&pane.title, cols_left);
+        }
+    }
+
+    fn render_pane_command(&self, line: &mut Line, cols_left: usize) {
+        let pane = self.get_focused_pane();
+        if let Some(command) = pane.and_then(|p| p.terminal_command.as_ref()) {
+            line.push_text(command, cols_left);
+        }
+    }
+
+    fn get_click_target(component: &Component) -> Option<ClickTarget> {
+        match component {
+            Component::Session => Some(ClickTarget::Session),
+            Component::Mode => Some(ClickTarget::Mode),
+            Component::SwapLayout => Some(ClickTarget::SwapLayout),
+            Component::KeyHints => Some(ClickTarget::KeyHints),
+            Component::Clock(_) => Some(ClickTarget::Clock),
+            Component::Command(name) => Some(ClickTarget::Command(name.to_string())),
+            Component::Var(key) => Some(ClickTarget::Var(key.to_string())),
+            Component::PaneTitle => Some(ClickTarget::PaneTitle),
+            Component::PaneCommand => Some(ClickTarget::PaneCommand),
+            Component::Clients => Some(ClickTarget::Clients),
+            _ => None,
+        }
+    }
+
+    /// Switches tabs on scroll, positive offset is scroll down.
+    fn on_scroll(&mut self, offset: isize) {
+        let offset = match self.tab_scroll {
+            TabScroll::Disabled => return,
+            TabScroll::UpNext => -offset,
+            TabScroll::UpPrevious => offset,
+        };
+
+        let len = self.tabs.len();
+        let target =
+            scroll::get_scrolled_tab(self.active_tab_idx, len, offset, self.tab_scroll_wrap);
+        if let Some(index) = target {
+            self.host.run(HostCommand::SwitchTab(index as u32));
+        }
+    }
+
+    /// Runs the action configured for the clicked region and button, returns true if the bar
+    /// changes. Left clicks on the errors summary or overlay show the next error, right clicks
+    /// hide the overlay.
+    fn on_click(&mut self, button: MouseButton, row: isize, col: usize) -> bool {
+        self.mouse_click_pos = col;
+        // regions are columns of the bar, debug lines can't be clicked
+        if row != self.bar_row as isize {
+            return false;
+        }
+        let regions = self.click_regions.borrow();
+        let (target, tab) = match regions.iter().find(|r| r.start <= col && col < r.end) {
+            Some(r) => (r.target.clone(), r.tab),
+            None => return false,
+        };
+        drop(regions);
+
+        if target == ClickTarget::Errors {
+            let next = self.error_overlay.map_or(0, |i| i + 1);
+            let shown = button == MouseButton::Left && next < self.errors.len();
+            self.error_overlay = shown.then_some(next);
+            return true;
+        }
+
+        let action = self
+            .mouse_actions
+            .iter()
+            .find(|(t, b, _)| *t == target && *b == button);
+        if let Some((_, _, action)) = action {
+            self.run_mouse_action(*action, tab);
+        }
+        false
+    }
+
+    fn run_mouse_action(&mut self, action: MouseAction, tab: Option<usize>) {
+        let switch_tab = tab.map(|index| HostCommand::SwitchTab(index as u32));
+
+        let commands = match action {
+            MouseAction::SwitchTab => switch_tab.into_iter().collect(),
+            MouseAction::NextSwapLayout => vec![HostCommand::NextSwapLayout],
+            MouseAction::PreviousSwapLayout => vec![HostCommand::PreviousSwapLayout],
+            MouseAction::ToggleLock => match self.mode_info.mode {
+                InputMode::Locked => vec![HostCommand::SwitchMode(InputMode::Normal)],
+                _ => vec![HostCommand::SwitchMode(InputMode::Locked)],
+            },
+            MouseAction::NewTab => vec![HostCommand::NewTab],
+            MouseAction::CloseTab => {
+                let iter = switch_tab.into_iter();
+                iter.chain([HostCommand::CloseFocusedTab]).collect()
+            }
+            MouseAction::RenameTab => {
+                let iter = switch_tab.into_iter();
+                iter.chain([HostCommand::SwitchMode(InputMode::RenameTab)])
+                    .collect()
+            }
+        };
+
+        for command in commands {
+            self.host.run(command);
+        }
+    }
+
+    fn render_layout_component(&self, line: &mut Line, component: &Component, cols_left: usize) {
+        match component {
+            Component::Text(t) => line.push_text(t, cols_left),
+            Component::Style(s) => self.render_context().apply_style(line, s),
+            Component::Session => self.render_session(line, cols_left),
+            Component::Mode => self.render_mode(line, cols_left),
+            Component::TabBar => self.render_tab_bar(line, cols_left),
+            Component::SwapLayout => self.render_swap_layout(line, cols_left),
+            Component::KeyHints => self.render_key_hints(line, cols_left),
+            Component::Clock(fmt) => self.render_clock(line, fmt, cols_left),
+            Component::Command(name) => self.render_command(line, name, cols_left),
+            Component::Var(key) => self.render_var(line, key, cols_left),
+            Component::PaneTitle => self.render_pane_title(line, cols_left),
+            Component::PaneCommand => self.render_pane_command(line, cols_left),
+            Component::Clients => self.render_session_clients(line, cols_left),
+            Component::LayoutHighlight {
+                layout,
+                hl_begin,
+                hl_end,
+            } => {
+                let ctx = self.render_context();
+                ctx.render_layout_highlight(line, cols_left, layout, *hl_begin, *hl_end)
+            }
+            _ => line.push_text("{unparsed}", cols_left),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::host::FakeHost;
+
+    fn strip_ansi(s: &str) -> String {
+        let mut res = String::new();
+        let mut in_escape = false;
+        for c in s.chars() {
+            match c {
+                '\x1b' => in_escape = true,
+                'm' if in_escape => in_escape = false,
+                _ if in_escape => {}
+                _ => res.push(c),
+            }
+        }
+        res
+    }
+
+    fn state(layout: &str, tabs_len: usize, active: usize) -> State<FakeHost> {
+        let mut state = State::<FakeHost>::default();
+        state.load(BTreeMap::new());
+        state.layout_components = State::<FakeHost>::parse_layout(layout).unwrap();
+        state.update_inputs();
+        let tabs = (0..tabs_len).map(|i| TabInfo {
+            position: i,
+            name: format!("tab{}", i + 1),
+            active: i == active,
+            ..Default::default()
+        });
+        state.update(Event::TabUpdate(tabs.collect()));
+        state
+    }
+
+    #[test]
+    fn renders_collapsed_tabs() {
+        let mut state = state("#T", 10, 5);
+        state.render(1, 40);
+        let output = strip_ansi(&state.host.output);
+
+        assert!(output.contains("tab6"));
+        assert!(!output.contains("tab1 "));
+        assert!(output.contains('←') && output.contains('→'));
+        assert_eq!(output.chars().count(), 40);
+    }
+
+    #[test]
+    fn switches_tab_on_click() {
+        let mut state = state("#T", 3, 0);
+        state.render(1, 80);
+        let regions = state.click_regions.borrow().clone();
+        let region = regions.iter().find(|r| r.tab == Some(2)).unwrap();
+
+        state.update(Event::Mouse(Mouse::LeftClick(0, region.start)));
+        assert_eq!(state.host.commands.last(), Some(&HostCommand::SwitchTab(2)));
+    }
+
+    #[test]
+    fn switches_to_previous_tab_on_scroll_down() {
+        let mut state = state("#T", 5, 3);
+        state.update(Event::Mouse(Mouse::ScrollDown(1)));
+        assert_eq!(state.host.commands.last(), Some(&HostCommand::SwitchTab(3)));
+    }
+
+    #[test]
+    fn shrinks_names_proportionally() {
+        let mut state = state("#T", 0, 0);
+        let configuration = [
+            ("layout", "#T"),
+            ("tab_layouts.active", "#N "),
+            ("tab_layouts.inactive", "#N "),
+            ("tab_overflow", "shrink"),
+        ];
+        let iter = configuration
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()));
+        state.load(iter.collect());
+        let tabs = ["main", "abcdefghijklmnopqrst", "abcdefghij"].map(|name| TabInfo {
+            name: name.to_string(),
+            active: name == "main",
+            ..Default::default()
+        });
+        state.update(Event::TabUpdate(tabs.to_vec()));
+
+        state.render(1, 22);
+        let output = strip_ansi(&state.host.output);
+        assert_eq!(output, "main abcdefghi… abcd… ");
+
+        // names are not shrunk below tab_name_min_len
+        state.render(1, 15);
+        let output = strip_ansi(&state.host.output);
+        assert_eq!(output, "main abc… abc… ");
+    }
+
+    #[test]
+    fn skips_render_of_unused_state() {
+        let mut state = state("#S", 3, 0);
+        state.render(1, 80);
+
+        let tabs = state.tabs.iter().map(|t| TabInfo {
+            name: "renamed".to_string(),
+            ..t.clone()
+        });
+        assert!(!state.update(Event::TabUpdate(tabs.collect())));
+
+        let mut mode_info = state.mode_info.clone();
+        mode_info.session_name = Some("renamed".to_string());
+        assert!(state.update(Event::ModeUpdate(mode_info)));
+    }
+
+    #[test]
+    fn skips_render_of_identical_output() {
+        let mut state = state("#T", 3, 0);
+        state.render(1, 80);
+        let output = state.host.output.clone();
+
+        let mut tabs = state.tabs.clone();
+        tabs[1].active_swap_layout_name = Some("VERTICAL".to_string());
+        assert!(!state.update(Event::TabUpdate(tabs.clone())));
+
+        tabs[1].name = "renamed".to_string();
+        assert!(state.update(Event::TabUpdate(tabs)));
+        state.render(1, 80);
+        assert_ne!(state.host.output, output);
+        assert!(strip_ansi(&state.host.output).contains("renamed"));
+    }
+
+    /// Run with `cargo test --release --target x86_64-unknown-linux-gnu -- --ignored
+    /// --nocapture benchmark`.
+    #[test]
+    #[ignore]
+    fn benchmark_many_tabs() {
+        let frames = 100;
+        for tabs_len in [100, 500, 1000] {
+            for overflow in [
+                TabOverflow::Balanced,
+                TabOverflow::Carousel,
+                TabOverflow::Shrink,
+            ] {
+                let mut state = state("#T", tabs_len, tabs_len / 2);
+                state.tab_overflow = overflow;
+
+                let start = Instant::now();
+                state.render(1, 200);
+                let first = start.elapsed();
+
+                let start = Instant::now();
+                for _ in 0..frames {
+                    state.render(1, 200);
+                }
+                let cached = start.elapsed() / frames;
+
+                assert_eq!(strip_ansi(&state.host.output).chars().count(), 200);
+                println!("{tabs_len} tabs, {overflow:?}: first frame {first:?}, cached {cached:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn shows_bound_key_hints() {
+        let mut state = state("#K", 1, 0);
+        let binds = vec![
+            (
+                Key::Ctrl('p'),
+                vec![actions::Action::SwitchToMode(InputMode::Pane)],
+            ),
+            (Key::Ctrl('q'), vec![actions::Action::Quit]),
+        ];
+        state.update(Event::ModeUpdate(ModeInfo {
+            keybinds: vec![(InputMode::Normal, binds)],
+            ..Default::default()
+        }));
+
+        let hints = state.get_key_hints();
+        let labels = hints.iter().map(|(_, label)| *label).collect::<Vec<_>>();
+        assert_eq!(labels, ["PANE", "QUIT"]);
+        assert_eq!(hints[0].0, Key::Ctrl('p').to_string());
+    }
+
+    #[test]
+    fn shows_debug_lines() {
+        let mut state = state("#T", 3, 1);
+        assert!(state.apply_message("debug on"));
+        state.render(3, 80);
+
+        let output = strip_ansi(&state.host.output);
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("mode:Normal tab:2/3 click:0 spacers:[56]"));
+        assert!(lines[1].starts_with("regions: Tab#1 0..8 Tab#2 8..16"));
+        assert!(lines[2].contains("tab2"));
+
+        state.render(1, 80);
+        let output = strip_ansi(&state.host.output);
+        assert!(output.starts_with("mode:Normal") && !output.contains('\n'));
+        assert!(state.click_regions.borrow().is_empty());
+
+        assert!(state.apply_message("debug"));
+        state.render(1, 80);
+        assert!(strip_ansi(&state.host.output).contains("tab2"));
+    }
+
+    #[test]
+    fn ignores_clicks_on_debug_lines() {
+        let mut state = state("#T", 3, 0);
+        state.apply_message("debug on");
+        state.render(3, 80);
+        let regions = state.click_regions.borrow().clone();
+        let region = regions.iter().find(|r| r.tab == Some(2)).unwrap();
+
+        state.update(Event::Mouse(Mouse::LeftClick(0, region.start)));
+        state.update(Event::Mouse(Mouse::LeftClick(1, region.start)));
+        assert!(state
+            .host
+            .commands
+            .iter()
+            .all(|c| *c != HostCommand::SwitchTab(2)));
+
+        state.update(Event::Mouse(Mouse::LeftClick(2, region.start)));
+        assert_eq!(state.host.commands.last(), Some(&HostCommand::SwitchTab(2)));
+    }
+
+    #[test]
+    fn shows_parse_errors() {
+        let mut state = state("#T", 1, 0);
+        let configuration = [
+            ("layout", "#T #Q"),
+            ("tab_layouts.active", "#[fg:blu] #N "),
+            ("tab_overflow", "sideways"),
+        ];
+        let iter = configuration
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()));
+        state.load(iter.collect());
+        state.render(1, 80);
+
+        let keys = state.errors.iter().map(|e| e.key.as_str());
+        assert_eq!(
+            keys.collect::<Vec<_>>(),
+            ["layout", "tab_layouts.active", "tab_overflow"]
+        );
+        // the rest of the bar is rendered from the defaults
+        let output = strip_ansi(&state.host.output);
+        assert!(output.starts_with(" ✗ 3 "));
+        assert!(output.contains("tab1"));
+        assert_eq!(state.tab_overflow, TabOverflow::Balanced);
+    }
+
+    #[test]
+    fn renders_nothing_for_missing_layouts() {
+        let mut settings = Settings::from(Config::default());
+        settings.mode_layouts.remove("tmux");
+        settings.tab_layouts.remove("active");
+
+        let mut state = State::<FakeHost>::default();
+        state.load_config(&settings, Vec::new());
+        let keys = state.errors.iter().map(|e| e.key.as_str());
+        assert_eq!(
+            keys.collect::<Vec<_>>(),
+            ["mode_layouts.tmux", "tab_layouts.active"]
+        );
+
+        state.update(Event::ModeUpdate(ModeInfo {
+            mode: InputMode::Tmux,
+            ..Default::default()
+        }));
+        state.update(Event::TabUpdate(vec![TabInfo {
+            active: true,
+            ..Default::default()
+        }]));
+        state.render(1, 80);
+        assert!(strip_ansi(&state.host.output).starts_with(" ✗ 2 "));
+    }
+
+    #[test]
+    fn cycles_error_overlay_on_click() {
+        let mut state = state("#T", 1, 0);
+        let configuration = [
+            ("tab_layouts.active", "#[fg:blu] #N "),
+            ("tab_overflow", "x"),
+        ];
+        let iter = configuration
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()));
+        state.load(iter.collect());
+        state.render(1, 80);
+
+        let click = |state: &mut State<FakeHost>, button| {
+            let event = match button {
+                MouseButton::Left => Mouse::LeftClick(0, 1),
+                MouseButton::Right => Mouse::RightClick(0, 1),
+            };
+            assert!(state.update(Event::Mouse(event)));
+            state.render(1, 80);
+            strip_ansi(&state.host.output)
+        };
+
+        let output = click(&mut state, MouseButton::Left);
+        assert!(output.starts_with(" 1/2 tab_layouts.active: Unknown color ^#[fg:blu]"));
+        assert!(output.contains("colors are black"));
+        assert_eq!(output.chars().count(), 80);
+        assert!(click(&mut state, MouseButton::Left).starts_with(" 2/2 tab_overflow: "));
+        assert!(click(&mut state, MouseButton::Left).starts_with(" ✗ 2 "));
+        click(&mut state, MouseButton::Left);
+        assert!(click(&mut state, MouseButton::Right).starts_with(" ✗ 2 "));
+    }
+}
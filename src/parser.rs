@@ -1,6 +1,7 @@
 use core::iter::Enumerate;
 use core::str::Chars;
 use std::iter::Peekable;
+use std::mem::discriminant;
 
 /// Set of components to show in the bar.
 #[derive(Debug, PartialEq)]
@@ -12,8 +13,28 @@ pub enum Component {
     Mode,
     TabBar,
     SwapLayout,
+    KeyHints,
+    /// Current time formatted with strftime like pattern.
+    Clock(String),
+    /// Output of the configured external command.
+    Command(String),
+    /// Value set through plugin messages.
+    Var(String),
+    PaneTitle,
+    PaneCommand,
+    TiledPanes,
+    FloatingPanes,
+    HiddenPanes,
+    /// Number of tabs.
+    TabCount,
+    /// Indices of the first and the last tabs hidden behind a collapsed tabs marker.
+    CollapsedFirst,
+    CollapsedLast,
+    /// Other clients focused on the tab, or number of clients in the session.
+    Clients,
     Index,
     Name,
+    Key,
     /// Layout string with highlighted part describing where parsing fails.
     LayoutHighlight {
         layout: String,
@@ -22,6 +43,7 @@ pub enum Component {
     },
 }
 
+/// Style set by `#[...]`, `Default` resets all styles.
 #[derive(Debug, PartialEq)]
 pub enum Style {
     Bg(Color),
@@ -30,6 +52,7 @@ pub enum Style {
     Default,
 }
 
+/// Color of the zellij palette.
 #[derive(Debug, PartialEq)]
 pub enum Color {
     Black,
@@ -49,15 +72,54 @@ pub enum Color {
     Brown,
 }
 
+/// Kind of the parsing error, so errors can be handled without matching their messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnknownColor,
+    UnknownStyle,
+    UnclosedBracket,
+    /// Known component which is not allowed in the template, or unknown char after `#`.
+    UnexpectedToken,
+    UnknownComponent,
+}
+
+impl ParseErrorKind {
+    fn context(self) -> &'static str {
+        match self {
+            ParseErrorKind::UnknownColor => "Unknown color: ",
+            ParseErrorKind::UnknownStyle => "Unknown style: ",
+            ParseErrorKind::UnclosedBracket => "Unclosed bracket: ",
+            ParseErrorKind::UnexpectedToken => "Unexpected token: ",
+            ParseErrorKind::UnknownComponent => "Unknown component: ",
+        }
+    }
+}
+
 /// Parsing error data.
 #[derive(Debug)]
 pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Error message for display, e.g. "Unknown component: ".
     pub context: String,
     pub layout: String,
+    /// Chars range of the layout where parsing fails.
     pub hl_begin: usize,
     pub hl_end: usize,
 }
 
+impl ParseError {
+    fn new(kind: ParseErrorKind, layout: &str, hl_begin: usize, hl_end: usize) -> ParseError {
+        ParseError {
+            kind,
+            context: kind.context().to_string(),
+            layout: layout.to_string(),
+            hl_begin,
+            hl_end,
+        }
+    }
+}
+
+/// Parser of the template, only text and allowed special components are accepted.
 pub struct Parser<'a> {
     layout: &'a str,
     iter: Peekable<Enumerate<Chars<'a>>>,
@@ -65,6 +127,8 @@ pub struct Parser<'a> {
 }
 
 impl<'a> Parser<'a> {
+    /// Components are compared by variant only, e.g. `Component::Clock(String::new())` allows
+    /// clocks with any format.
     pub fn new(layout: &'a str, allowed_specials: Vec<Component>) -> Parser<'a> {
         Parser {
             layout,
@@ -74,7 +138,7 @@ impl<'a> Parser<'a> {
     }
 
     /// Parse color.
-    fn parse_color(token: &str) -> Result<Style, &str> {
+    fn parse_color(token: &str) -> Result<Style, ParseErrorKind> {
         let v: Vec<&str> = token.split(':').collect();
 
         let color = match v.get(1).cloned() {
@@ -94,24 +158,24 @@ impl<'a> Parser<'a> {
             Some("pink") => Color::Pink,
             Some("brown") => Color::Brown,
             _ => {
-                return Err("Unknown color: ");
+                return Err(ParseErrorKind::UnknownColor);
             }
         };
 
-        match v.get(0).cloned() {
+        match v.first().cloned() {
             Some("fg") => Ok(Style::Fg(color)),
             Some("bg") => Ok(Style::Bg(color)),
-            _ => Err("Unknown color: "),
+            _ => Err(ParseErrorKind::UnknownColor),
         }
     }
 
     /// Parse style inside #[].
-    fn parse_style(token: &str) -> Result<Style, &str> {
+    fn parse_style(token: &str) -> Result<Style, ParseErrorKind> {
         match token {
             "default" => Ok(Style::Default),
             "bold" => Ok(Style::Bold),
             _ if token.contains(':') => Ok(Self::parse_color(token)?),
-            _ => Err("Unknown style: "),
+            _ => Err(ParseErrorKind::UnknownStyle),
         }
     }
 
@@ -130,37 +194,101 @@ impl<'a> Parser<'a> {
                     Some((_, ',')) => self.iter.next(),
                     Some((_, ']')) => return Ok(res),
                     _ => {
-                        return Err(ParseError {
-                            context: "Unclosed bracket: ".to_string(),
-                            layout: self.layout.to_string(),
+                        return Err(ParseError::new(
+                            ParseErrorKind::UnclosedBracket,
+                            self.layout,
                             hl_begin,
-                            hl_end: hl_begin + 1,
-                        })
+                            hl_begin + 1,
+                        ))
                     }
                 };
             } else if let Err(e) = style {
                 match self.iter.peek() {
                     Some((i, ',')) | Some((i, ']')) => {
-                        return Err(ParseError {
-                            context: e.to_string(),
-                            layout: self.layout.to_string(),
-                            hl_begin: i.saturating_sub(token.chars().count()),
-                            hl_end: *i,
-                        })
+                        return Err(ParseError::new(
+                            e,
+                            self.layout,
+                            i.saturating_sub(token.chars().count()),
+                            *i,
+                        ))
                     }
-                    Some((_, '#')) | _ => {
-                        return Err(ParseError {
-                            context: "Unclosed bracket: ".to_string(),
-                            layout: self.layout.to_string(),
+                    _ => {
+                        return Err(ParseError::new(
+                            ParseErrorKind::UnclosedBracket,
+                            self.layout,
                             hl_begin,
-                            hl_end: hl_begin + 1,
-                        })
+                            hl_begin + 1,
+                        ))
                     }
                 }
             }
         }
     }
 
+    /// Parse long form of components inside #{}, e.g. #{keys}.
+    fn parse_long_special(&mut self) -> Result<Vec<Component>, ParseError> {
+        let hl_begin = self.iter.next().map(|(i, _)| i).unwrap(); // skips '{'
+        let token = self.take_until_any("}#");
+
+        let hl_end = match self.iter.peek() {
+            Some((i, '}')) => *i,
+            _ => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnclosedBracket,
+                    self.layout,
+                    hl_begin,
+                    hl_begin + 1,
+                ))
+            }
+        };
+
+        let (name, arg) = match token.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (token.as_str(), None),
+        };
+
+        let component = match (name, arg) {
+            ("keys", None) => Some(Component::KeyHints),
+            ("time", arg) => Some(Component::Clock(arg.unwrap_or("%H:%M").to_string())),
+            ("date", arg) => Some(Component::Clock(arg.unwrap_or("%Y-%m-%d").to_string())),
+            ("cmd", Some(name)) => Some(Component::Command(name.to_string())),
+            ("var", Some(key)) => Some(Component::Var(key.to_string())),
+            ("pane.title", None) => Some(Component::PaneTitle),
+            ("pane.command", None) => Some(Component::PaneCommand),
+            ("panes.tiled", None) => Some(Component::TiledPanes),
+            ("panes.floating", None) => Some(Component::FloatingPanes),
+            ("panes.hidden", None) => Some(Component::HiddenPanes),
+            ("clients", None) => Some(Component::Clients),
+            ("tabs", None) => Some(Component::TabCount),
+            ("collapsed.first", None) => Some(Component::CollapsedFirst),
+            ("collapsed.last", None) => Some(Component::CollapsedLast),
+            _ => None,
+        };
+
+        match component {
+            Some(c)
+                if self
+                    .allowed_specials
+                    .iter()
+                    .any(|x| discriminant(x) == discriminant(&c)) =>
+            {
+                Ok(vec![c])
+            }
+            Some(_) => Err(ParseError::new(
+                ParseErrorKind::UnexpectedToken,
+                self.layout,
+                hl_begin + 1,
+                hl_end,
+            )),
+            None => Err(ParseError::new(
+                ParseErrorKind::UnknownComponent,
+                self.layout,
+                hl_begin + 1,
+                hl_end,
+            )),
+        }
+    }
+
     /// Parse non text components.
     fn parse_specials(&mut self) -> Result<Vec<Component>, ParseError> {
         macro_rules! is_allowed {
@@ -178,20 +306,23 @@ impl<'a> Parser<'a> {
             Some((_, 'I')) if is_allowed!(Component::Index) => Ok(vec![Component::Index]),
             Some((_, 'N')) if is_allowed!(Component::Name) => Ok(vec![Component::Name]),
             Some((_, 'L')) if is_allowed!(Component::SwapLayout) => Ok(vec![Component::SwapLayout]),
+            Some((_, 'K')) if is_allowed!(Component::KeyHints) => Ok(vec![Component::KeyHints]),
+            Some((_, 'K')) if is_allowed!(Component::Key) => Ok(vec![Component::Key]),
             Some((_, '_')) if is_allowed!(Component::Spacer) => Ok(vec![Component::Spacer]),
             Some((_, '[')) if is_allowed!(Component::Style(..)) => Ok(self.parse_style_group()?),
-            Some((hl_begin, _)) => Err(ParseError {
-                context: "Unexpected token: ".to_string(),
-                layout: self.layout.to_string(),
-                hl_begin: *hl_begin,
-                hl_end: *hl_begin + 1,
-            }),
-            None => Err(ParseError {
-                context: "Unexpected token: ".to_string(),
-                layout: self.layout.to_string(),
-                hl_begin: self.layout.chars().count() - 1,
-                hl_end: self.layout.chars().count(),
-            }),
+            Some((_, '{')) => Ok(self.parse_long_special()?),
+            Some((hl_begin, _)) => Err(ParseError::new(
+                ParseErrorKind::UnexpectedToken,
+                self.layout,
+                *hl_begin,
+                *hl_begin + 1,
+            )),
+            None => Err(ParseError::new(
+                ParseErrorKind::UnexpectedToken,
+                self.layout,
+                self.layout.chars().count() - 1,
+                self.layout.chars().count(),
+            )),
         };
 
         self.iter.next(); // it should be 'S' | 'M' | ']' ...
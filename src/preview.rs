@@ -0,0 +1,292 @@
+//! Native binary rendering a preset merged with plugin configuration read from a TOML file,
+//! with synthetic tabs in every mode at several widths, so layouts can be designed without
+//! rebuilding the plugin and restarting zellij.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use tbar::clock;
+use tbar::command::Executor;
+use tbar::host::{Host, HostCommand};
+use tbar::sample;
+use tbar::settings::{Named, Settings};
+use tbar::State;
+use zellij_tile::prelude::*;
+
+const USAGE: &str = "\
+Usage: tbar-preview [OPTIONS] [CONFIG]
+
+Renders the preset merged with plugin configuration from the CONFIG TOML file, with keys
+like in the plugin block of zellij layout and layouts of modes and states in tables, e.g.
+`[tab_layouts]`.
+
+Options:
+  --preset <NAME>      compact-bar, minimal or template [default: compact-bar]
+  --tabs <N>           number of tabs [default: 8]
+  --active <N>         1-based index of the active tab [default: 3]
+  --modes <NAMES>      comma separated modes, e.g. normal,locked [default: all]
+  --palette <NAME>     ansi, dracula or gruvbox [default: ansi]
+  --widths <COLS>      comma separated widths [default: 60,100,160]
+  --watch              render again when CONFIG changes";
+
+/// Host ignoring calls to zellij, the bar is printed by the preview.
+#[derive(Default)]
+struct PreviewHost {
+    output: String,
+}
+
+impl Host for PreviewHost {
+    fn run(&mut self, _command: HostCommand) {}
+
+    fn print(&mut self, output: &str) {
+        self.output = output.to_string();
+    }
+
+    fn now(&self) -> f64 {
+        clock::unix_now()
+    }
+}
+
+impl Executor for PreviewHost {
+    fn execute(&mut self, _command: &[String], _context: BTreeMap<String, String>) {}
+}
+
+struct Options {
+    preset: Settings,
+    config: Option<PathBuf>,
+    tabs: usize,
+    active: usize,
+    modes: Vec<(&'static str, InputMode)>,
+    palette: Palette,
+    widths: Vec<usize>,
+    watch: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            preset: sample::preset("compact-bar").unwrap(),
+            config: None,
+            tabs: 8,
+            active: 3,
+            modes: InputMode::NAMES.to_vec(),
+            palette: sample::palette(),
+            widths: vec![60, 100, 160],
+            watch: false,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value of {arg}"));
+            match arg.as_str() {
+                "--preset" => {
+                    let name = value()?;
+                    options.preset =
+                        sample::preset(&name).ok_or(format!("Unknown preset: {name}"))?;
+                }
+                "--tabs" => options.tabs = parse_number(&value()?)?,
+                "--active" => options.active = parse_number(&value()?)?,
+                "--modes" => {
+                    let names = value()?;
+                    let modes = names.split(',').map(|name| {
+                        let mode = InputMode::NAMES.iter().find(|(n, _)| *n == name);
+                        mode.copied().ok_or(format!("Unknown mode: {name}"))
+                    });
+                    options.modes = modes.collect::<Result<_, _>>()?;
+                }
+                "--palette" => {
+                    let name = value()?;
+                    options.palette =
+                        get_palette(&name).ok_or(format!("Unknown palette: {name}"))?;
+                }
+                "--widths" => {
+                    let widths = value()?;
+                    let widths = widths.split(',').map(parse_number);
+                    options.widths = widths.collect::<Result<_, _>>()?;
+                }
+                "--watch" => options.watch = true,
+                _ if arg.starts_with('-') => return Err(format!("Unknown option: {arg}")),
+                _ => options.config = Some(PathBuf::from(arg)),
+            }
+        }
+
+        if options.active == 0 || options.active > options.tabs {
+            return Err(format!("Active tab {} is out of tabs", options.active));
+        }
+        if options.watch && options.config.is_none() {
+            return Err("Watch needs the CONFIG file".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|_| format!("Invalid number: {s}"))
+}
+
+fn rgb(hex: u32) -> PaletteColor {
+    PaletteColor::Rgb(((hex >> 16) as u8, (hex >> 8) as u8, hex as u8))
+}
+
+fn get_palette(name: &str) -> Option<Palette> {
+    let palette = match name {
+        "ansi" => sample::palette(),
+        "dracula" => Palette {
+            black: rgb(0x282a36),
+            red: rgb(0xff5555),
+            green: rgb(0x50fa7b),
+            yellow: rgb(0xf1fa8c),
+            blue: rgb(0x6272a4),
+            magenta: rgb(0xff79c6),
+            cyan: rgb(0x8be9fd),
+            white: rgb(0xf8f8f2),
+            orange: rgb(0xffb86c),
+            gray: rgb(0x44475a),
+            purple: rgb(0xbd93f9),
+            gold: rgb(0xf1fa8c),
+            silver: rgb(0xbfbfbf),
+            pink: rgb(0xff79c6),
+            brown: rgb(0xb07d48),
+            ..Default::default()
+        },
+        "gruvbox" => Palette {
+            black: rgb(0x282828),
+            red: rgb(0xcc241d),
+            green: rgb(0x98971a),
+            yellow: rgb(0xd79921),
+            blue: rgb(0x458588),
+            magenta: rgb(0xb16286),
+            cyan: rgb(0x689d6a),
+            white: rgb(0xfbf1c7),
+            orange: rgb(0xd65d0e),
+            gray: rgb(0x928374),
+            purple: rgb(0xb16286),
+            gold: rgb(0xfabd2f),
+            silver: rgb(0xbdae93),
+            pink: rgb(0xd3869b),
+            brown: rgb(0xaf3a03),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some(palette)
+}
+
+/// Reads plugin configuration from the TOML file, keys of tables are joined with names of
+/// their entries, e.g. `tab_layouts.active`.
+fn read_plugin_config(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let table = text
+        .parse::<toml::Table>()
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let mut res = BTreeMap::new();
+    for (key, value) in table {
+        match value {
+            toml::Value::Table(layouts) => {
+                for (name, layout) in layouts {
+                    res.insert(format!("{key}.{name}"), to_plugin_value(layout));
+                }
+            }
+            value => {
+                res.insert(key, to_plugin_value(value));
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Returns the value as written in the plugin block, strings don't need quotes there.
+fn to_plugin_value(value: toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+/// Renders bars of every mode and width, followed by the error overlay of every config error.
+fn render_preview(options: &Options) -> Result<String, String> {
+    let (settings, errors) = match &options.config {
+        Some(path) => Settings::from_plugin_config(&read_plugin_config(path)?),
+        None => (Settings::default(), Vec::new()),
+    };
+    let tabs = sample::tabs(options.tabs, options.active - 1);
+    let mut state = sample::state::<PreviewHost>(&options.preset, options.palette, tabs);
+    // like the plugin, layouts which fail to parse keep the ones of the preset
+    state.load_config(&options.preset.clone().merge(settings), errors);
+
+    let max_width = options.widths.iter().max().copied().unwrap_or(80);
+    let label_len = max_width.to_string().len();
+    let render = |state: &mut State<PreviewHost>, width: usize| {
+        state.render(1, width);
+        format!("{width:>label_len$} {}\n", state.host().output)
+    };
+
+    let mut res = String::new();
+    for (name, mode) in &options.modes {
+        state.update(Event::ModeUpdate(sample::mode_info(*mode, options.palette)));
+        res.push_str(&format!("{name}\n"));
+        for width in &options.widths {
+            res.push_str(&render(&mut state, *width));
+        }
+    }
+
+    if !state.errors().is_empty() {
+        res.push_str("errors\n");
+    }
+    for i in 0..state.errors().len() {
+        state.show_error(i);
+        res.push_str(&render(&mut state, max_width));
+    }
+    Ok(res)
+}
+
+/// Renders the preview every time the config file is modified.
+fn watch(options: &Options) -> ! {
+    let path = options.config.as_ref().unwrap();
+    // None until the first render, so a missing file is reported too
+    let mut last_modified = None;
+
+    loop {
+        let modified = Some(fs::metadata(path).and_then(|m| m.modified()).ok());
+        if modified != last_modified {
+            last_modified = modified;
+            // clear the screen
+            print!("\x1b[2J\x1b[H");
+            match render_preview(options) {
+                Ok(output) => print!("{output}"),
+                Err(e) => println!("{e}"),
+            }
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn main() {
+    if env::args().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return;
+    }
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}, see --help");
+            process::exit(2);
+        }
+    };
+
+    if options.watch {
+        watch(&options);
+    }
+    match render_preview(&options) {
+        Ok(output) => print!("{output}"),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+}
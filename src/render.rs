@@ -0,0 +1,265 @@
+use std::cmp::{max, min};
+
+use zellij_tile::prelude::*;
+
+use crate::parser::{Color, Component, Style};
+
+const RESET: &str = "\x1b[0m";
+
+/// Style of a span, colors set to None are terminal defaults.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct SpanStyle {
+    pub fg: Option<ansi_term::Color>,
+    pub bg: Option<ansi_term::Color>,
+    pub bold: bool,
+}
+
+impl SpanStyle {
+    /// Returns escape sequence switching the terminal from prev style to this one.
+    fn transition_from(&self, prev: &SpanStyle) -> String {
+        let needs_reset = (prev.fg.is_some() && self.fg.is_none())
+            || (prev.bg.is_some() && self.bg.is_none())
+            || (prev.bold && !self.bold);
+        let from = if needs_reset {
+            SpanStyle::default()
+        } else {
+            *prev
+        };
+
+        let mut style = ansi_term::Style::new();
+        if self.fg != from.fg {
+            style.foreground = self.fg;
+        }
+        if self.bg != from.bg {
+            style.background = self.bg;
+        }
+        style.is_bold = self.bold && !from.bold;
+
+        let reset = if needs_reset { RESET } else { "" };
+        format!("{}{}", reset, style.prefix())
+    }
+}
+
+/// Text sharing the same style.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Span {
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+/// Line of styled spans, text is pushed with the current style.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Line {
+    pub spans: Vec<Span>,
+    /// Style of the next pushed text.
+    pub style: SpanStyle,
+}
+
+impl Line {
+    pub fn new(style: SpanStyle) -> Line {
+        Line {
+            spans: Vec::new(),
+            style,
+        }
+    }
+
+    /// Returns empty line continuing with the current style, used to render parts which may be
+    /// dropped if they don't fit.
+    pub fn fork(&self) -> Line {
+        Line::new(self.style)
+    }
+
+    /// Pushes text cut to cols_left chars.
+    pub fn push_text(&mut self, text: &str, cols_left: usize) {
+        let text = if cols_left < text.chars().count() {
+            text.chars().take(cols_left).collect()
+        } else {
+            text.to_string()
+        };
+        let style = self.style;
+        self.spans.push(Span { text, style });
+    }
+
+    /// Appends spans of the other line and continues with its style.
+    pub fn append(&mut self, other: Line) {
+        self.spans.extend(other.spans);
+        self.style = other.style;
+    }
+
+    /// Returns number of columns taken by the line.
+    pub fn width(&self) -> usize {
+        self.spans.iter().map(|s| s.text.chars().count()).sum()
+    }
+
+    /// Returns text of the line without styles.
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    /// Serializes the line with ANSI escape sequences, emitted only where the style changes.
+    /// Styled lines end with a reset, so the next line starts from the default style.
+    pub fn to_ansi(&self) -> String {
+        let mut res = String::new();
+        let mut prev = SpanStyle::default();
+
+        for span in self.spans.iter().filter(|s| !s.text.is_empty()) {
+            res.push_str(&span.style.transition_from(&prev));
+            res.push_str(&span.text);
+            prev = span.style;
+        }
+        if prev != SpanStyle::default() {
+            res.push_str(RESET);
+        }
+
+        res
+    }
+}
+
+/// Renders parsed templates into lines with colors of the zellij palette.
+pub struct RenderContext<'a> {
+    palette: &'a Palette,
+}
+
+impl<'a> RenderContext<'a> {
+    pub fn new(palette: &'a Palette) -> RenderContext<'a> {
+        RenderContext { palette }
+    }
+
+    /// Returns the palette color as terminal color.
+    pub fn get_ansi_color(&self, color: &Color) -> ansi_term::Color {
+        let palette = self.palette;
+        let p = match color {
+            Color::Black => palette.black,
+            Color::Red => palette.red,
+            Color::Green => palette.green,
+            Color::Yellow => palette.yellow,
+            Color::Blue => palette.blue,
+            Color::Magenta => palette.magenta,
+            Color::Cyan => palette.cyan,
+            Color::White => palette.white,
+            Color::Orange => palette.orange,
+            Color::Gray => palette.gray,
+            Color::Purple => palette.purple,
+            Color::Gold => palette.gold,
+            Color::Silver => palette.silver,
+            Color::Pink => palette.pink,
+            Color::Brown => palette.brown,
+        };
+
+        match p {
+            PaletteColor::Rgb((r, g, b)) => ansi_term::Color::RGB(r, g, b),
+            PaletteColor::EightBit(color) => ansi_term::Color::Fixed(color),
+        }
+    }
+
+    /// Applies the style to the text pushed to the line after it.
+    pub fn apply_style(&self, line: &mut Line, style: &Style) {
+        match style {
+            Style::Fg(c) => line.style.fg = Some(self.get_ansi_color(c)),
+            Style::Bg(c) => line.style.bg = Some(self.get_ansi_color(c)),
+            Style::Bold => line.style.bold = true,
+            Style::Default => line.style = SpanStyle::default(),
+        }
+    }
+
+    /// Renders the layout around the highlighted part, squeezed to fit cols_left.
+    pub fn render_layout_highlight(
+        &self,
+        line: &mut Line,
+        cols_left: usize,
+        layout: &str,
+        hl_begin: usize,
+        hl_end: usize,
+    ) {
+        // Func constants
+        let layout_wrap_len = 6;
+        let layout_len = layout.chars().count();
+        let hl_len = hl_end.saturating_sub(hl_begin);
+
+        // Calculate layout window beginning and end
+        let offset = cols_left.saturating_sub(hl_len + layout_wrap_len) / 2;
+        let layout_begin = hl_begin.saturating_sub(offset);
+        let layout_end = min(layout_len, hl_end + offset);
+
+        // Setup layout wrapping strings
+        let wrap_left = if layout_begin > 0 { "..." } else { "^" };
+        let wrap_right = if layout_end < layout_len { "..." } else { "$" };
+
+        // Squeeze highlighted text if needed.
+        let squeeze_size = (hl_len + layout_wrap_len).saturating_sub(cols_left);
+        let hl_end_squeezed = max(hl_begin, hl_end.saturating_sub(squeeze_size));
+        if hl_end_squeezed <= hl_begin {
+            line.push_text("......", cols_left);
+            return;
+        };
+
+        // handle string slice with unicode chars
+        let layout_unicode_slice = |start, end| {
+            let l = layout.chars().collect::<Vec<_>>();
+            l.get(start..end).unwrap().iter().collect::<String>()
+        };
+        let layout_before_hl = layout_unicode_slice(layout_begin, hl_begin);
+        let layout_hl = layout_unicode_slice(hl_begin, hl_end_squeezed);
+        let layout_after_hl = layout_unicode_slice(hl_end, layout_end);
+
+        line.push_text(&format!("{wrap_left}{layout_before_hl}"), usize::MAX);
+        self.apply_style(line, &Style::Bg(Color::Yellow));
+        line.push_text(&layout_hl, usize::MAX);
+        self.apply_style(line, &Style::Bg(Color::Red));
+        line.push_text(&format!("{layout_after_hl}{wrap_right}"), usize::MAX);
+    }
+
+    /// Renders text and styles of the template, other components are rendered by
+    /// render_special.
+    pub fn render<F>(&self, line: &mut Line, components: &[Component], mut render_special: F)
+    where
+        F: FnMut(&mut Line, &Component),
+    {
+        for c in components {
+            match c {
+                Component::Text(t) => line.push_text(t, usize::MAX),
+                Component::Style(s) => self.apply_style(line, s),
+                _ => render_special(line, c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_styles_only_on_change() {
+        let palette = Palette::default();
+        let ctx = RenderContext::new(&palette);
+        let mut line = Line::default();
+        ctx.apply_style(&mut line, &Style::Bold);
+        line.push_text("a", usize::MAX);
+        line.push_text("b", usize::MAX);
+        ctx.apply_style(&mut line, &Style::Default);
+        line.push_text("c", usize::MAX);
+
+        assert_eq!(line.spans.len(), 3);
+        assert_eq!(line.to_ansi(), "\x1b[1mab\x1b[0mc");
+
+        ctx.apply_style(&mut line, &Style::Bold);
+        line.push_text("d", usize::MAX);
+        assert_eq!(line.to_ansi(), "\x1b[1mab\x1b[0mc\x1b[1md\x1b[0m");
+    }
+
+    #[test]
+    fn cuts_text_and_appends_forked_parts() {
+        let mut line = Line::default();
+        line.push_text("abc", 2);
+        assert_eq!(line.text(), "ab");
+
+        let mut part = line.fork();
+        part.push_text("def", usize::MAX);
+        assert_eq!(part.width(), 3);
+        assert_eq!(line.width(), 2);
+
+        line.append(part);
+        assert_eq!(line.text(), "abdef");
+    }
+}
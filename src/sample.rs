@@ -0,0 +1,202 @@
+//! Presets and synthetic zellij state, shared by snapshot tests and the preview binary.
+
+use std::collections::BTreeMap;
+
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+use crate::command::Executor;
+use crate::config::Config;
+use crate::host::Host;
+use crate::settings::Settings;
+use crate::State;
+
+#[path = "../configs/compact-bar.rs"]
+mod compact_bar;
+#[path = "../configs/minimal.rs"]
+mod minimal;
+#[path = "../configs/_template.rs"]
+mod template;
+
+/// Presets define own Config types, their fields are moved to the plugin Config, which is
+/// converted to settings.
+macro_rules! preset {
+    ($preset:ident) => {{
+        let c = $preset::Config::default();
+        let cfg = Config {
+            layout: c.layout,
+            mode_layouts: c.mode_layouts,
+            tab_layouts: c.tab_layouts,
+            tab_overflow: c.tab_overflow,
+            tab_name_min_len: c.tab_name_min_len,
+            collapsed_click: c.collapsed_click,
+            mouse_actions: c.mouse_actions,
+            tab_scroll: c.tab_scroll,
+            tab_scroll_wrap: c.tab_scroll_wrap,
+            swap_layouts: c.swap_layouts,
+            key_hints: c.key_hints,
+            key_hint_layouts: c.key_hint_layouts,
+            utc_offset_minutes: c.utc_offset_minutes,
+            commands: c.commands,
+            command_layouts: c.command_layouts,
+            client_layouts: c.client_layouts,
+        };
+        Settings::from(cfg)
+    }};
+}
+
+/// Returns settings of the preset in `./configs`, `_template.rs` is named `template`.
+pub fn preset(name: &str) -> Option<Settings> {
+    match name {
+        "compact-bar" => Some(preset!(compact_bar)),
+        "minimal" => Some(preset!(minimal)),
+        "template" => Some(preset!(template)),
+        _ => None,
+    }
+}
+
+/// Palette with distinct 8-bit colors, so annotations show which color is used.
+pub fn palette() -> Palette {
+    Palette {
+        black: PaletteColor::EightBit(0),
+        red: PaletteColor::EightBit(1),
+        green: PaletteColor::EightBit(2),
+        yellow: PaletteColor::EightBit(3),
+        blue: PaletteColor::EightBit(4),
+        magenta: PaletteColor::EightBit(5),
+        cyan: PaletteColor::EightBit(6),
+        white: PaletteColor::EightBit(7),
+        orange: PaletteColor::EightBit(208),
+        gray: PaletteColor::EightBit(8),
+        purple: PaletteColor::EightBit(93),
+        gold: PaletteColor::EightBit(220),
+        silver: PaletteColor::EightBit(250),
+        pink: PaletteColor::EightBit(213),
+        brown: PaletteColor::EightBit(130),
+        ..Default::default()
+    }
+}
+
+/// Returns mode info of the session `main`, keys switch modes and quit like in the default
+/// keybinds of zellij.
+pub fn mode_info(mode: InputMode, palette: Palette) -> ModeInfo {
+    let modes = [
+        ('g', InputMode::Locked),
+        ('p', InputMode::Pane),
+        ('t', InputMode::Tab),
+        ('n', InputMode::Resize),
+        ('h', InputMode::Move),
+        ('s', InputMode::Scroll),
+        ('o', InputMode::Session),
+    ];
+    let mut binds = modes
+        .iter()
+        .filter(|(_, m)| mode != InputMode::Locked || *m == InputMode::Locked)
+        .map(|(c, m)| {
+            // the key of the current mode returns to normal mode
+            let target = if *m == mode { InputMode::Normal } else { *m };
+            (Key::Ctrl(*c), vec![Action::SwitchToMode(target)])
+        })
+        .collect::<Vec<_>>();
+    if mode != InputMode::Locked {
+        binds.push((Key::Ctrl('q'), vec![Action::Quit]));
+    }
+
+    let mut mode_info = ModeInfo {
+        mode,
+        keybinds: vec![(mode, binds)],
+        session_name: Some("main".to_string()),
+        ..Default::default()
+    };
+    mode_info.style.colors = palette;
+    mode_info
+}
+
+/// Returns tabs covering every tab state: the second tab syncs panes, the sixth is fullscreen,
+/// the seventh shows floating panes and the swap layout of the active one is dirty. Another
+/// client is focused on the first tab.
+pub fn tabs(len: usize, active: usize) -> Vec<TabInfo> {
+    let names = [
+        "editor", "server", "logs", "", "Tab #5", "database", "tests", "docs",
+    ];
+    let tabs = (0..len).map(|i| TabInfo {
+        position: i,
+        name: names
+            .get(i)
+            .map_or(format!("tab{}", i + 1), |n| n.to_string()),
+        active: i == active,
+        is_sync_panes_active: i == 1,
+        is_fullscreen_active: i == 5,
+        are_floating_panes_visible: i == 6,
+        active_swap_layout_name: Some("VERTICAL".to_string()),
+        is_swap_layout_dirty: i == active,
+        other_focused_clients: if i == 0 { vec![2] } else { vec![] },
+        ..Default::default()
+    });
+    tabs.collect()
+}
+
+/// Returns one pane for each tab, the focused pane of the active tab runs an editor. When
+/// `changed` is set, the title of the fifth tab changes and the command of the eighth one
+/// exits, so they get activity and bell flags.
+pub fn panes(tabs: &[TabInfo], changed: bool) -> PaneManifest {
+    let panes = tabs.iter().map(|t| {
+        let (title, command) = match t.position {
+            _ if t.active => ("nvim src/main.rs", Some("nvim src/main.rs")),
+            4 if changed => ("htop", None),
+            7 => ("mdbook serve", Some("mdbook serve")),
+            _ => ("zsh", None),
+        };
+        let pane = PaneInfo {
+            id: t.position as u32,
+            is_focused: true,
+            title: title.to_string(),
+            exited: changed && t.position == 7,
+            terminal_command: command.map(|c| c.to_string()),
+            ..Default::default()
+        };
+        (t.position, vec![pane])
+    });
+
+    PaneManifest {
+        panes: panes.collect(),
+    }
+}
+
+/// Executor recording commands, so their results can be sent back to the state.
+#[derive(Default)]
+struct Recorder(Vec<(Vec<String>, BTreeMap<String, String>)>);
+
+impl Executor for Recorder {
+    fn execute(&mut self, command: &[String], context: BTreeMap<String, String>) {
+        self.0.push((command.to_vec(), context));
+    }
+}
+
+/// Returns state with the settings loaded, showing the tabs and their panes in normal mode.
+/// Commands are run once and output their own command line.
+pub fn state<H: Host + Default>(
+    settings: &Settings,
+    palette: Palette,
+    tabs: Vec<TabInfo>,
+) -> State<H> {
+    let mut state = State::<H>::default();
+    state.load_config(settings, Vec::new());
+    state.update(Event::ModeUpdate(mode_info(InputMode::Normal, palette)));
+    state.update(Event::TabUpdate(tabs.clone()));
+    state.update(Event::PaneUpdate(panes(&tabs, false)));
+    state.update(Event::PaneUpdate(panes(&tabs, true)));
+
+    let mut recorder = Recorder::default();
+    state.commands.tick(state.host.now(), &mut recorder);
+    for (command, context) in recorder.0 {
+        let stdout = command.join(" ").into_bytes();
+        state.update(Event::RunCommandResult(
+            Some(0),
+            stdout,
+            Vec::new(),
+            context,
+        ));
+    }
+    state
+}
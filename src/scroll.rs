@@ -0,0 +1,50 @@
+/// Returns 1-based index of the tab to switch to after scrolling from the 0-based active tab by
+/// offset tabs, None if there is no such tab.
+pub fn get_scrolled_tab(active: usize, len: usize, offset: isize, wrap: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    let target = active as isize + offset;
+    let target = if wrap {
+        target.rem_euclid(len as isize)
+    } else {
+        target.clamp(0, len as isize - 1)
+    };
+
+    match target as usize {
+        t if t == active => None,
+        t => Some(t + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switches_to_adjacent_tab() {
+        assert_eq!(get_scrolled_tab(0, 3, 1, false), Some(2));
+        assert_eq!(get_scrolled_tab(1, 3, 1, false), Some(3));
+        assert_eq!(get_scrolled_tab(2, 3, -1, false), Some(2));
+        assert_eq!(get_scrolled_tab(1, 3, -1, false), Some(1));
+    }
+
+    #[test]
+    fn stops_at_edges_without_wrap() {
+        assert_eq!(get_scrolled_tab(2, 3, 1, false), None);
+        assert_eq!(get_scrolled_tab(0, 3, -1, false), None);
+    }
+
+    #[test]
+    fn wraps_around_edges() {
+        assert_eq!(get_scrolled_tab(2, 3, 1, true), Some(1));
+        assert_eq!(get_scrolled_tab(0, 3, -1, true), Some(3));
+    }
+
+    #[test]
+    fn ignores_single_and_missing_tabs() {
+        assert_eq!(get_scrolled_tab(0, 1, 1, true), None);
+        assert_eq!(get_scrolled_tab(0, 0, -1, true), None);
+    }
+}
@@ -0,0 +1,508 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+use serde::Deserialize;
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+use crate::command::CommandSettings;
+use crate::config::Config;
+use crate::error::ConfigError;
+use crate::{
+    ClickTarget, ClientsState, CollapsedClick, CommandState, KeyHintState, MouseAction,
+    MouseButton, SwapLayoutState, TabOverflow, TabPartState, TabScroll,
+};
+const MODES: [(&str, InputMode); 14] = [
+    ("normal", InputMode::Normal),
+    ("locked", InputMode::Locked),
+    ("resize", InputMode::Resize),
+    ("pane", InputMode::Pane),
+    ("tab", InputMode::Tab),
+    ("scroll", InputMode::Scroll),
+    ("enter_search", InputMode::EnterSearch),
+    ("search", InputMode::Search),
+    ("rename_tab", InputMode::RenameTab),
+    ("rename_pane", InputMode::RenamePane),
+    ("session", InputMode::Session),
+    ("move", InputMode::Move),
+    ("prompt", InputMode::Prompt),
+    ("tmux", InputMode::Tmux),
+];
+
+const TAB_STATES: [(&str, TabPartState); 13] = [
+    ("inactive", TabPartState::Inactive),
+    ("active", TabPartState::Active),
+    ("inactive_sync", TabPartState::InactiveSync),
+    ("active_sync", TabPartState::ActiveSync),
+    ("inactive_fullscreen", TabPartState::InactiveFullscreen),
+    ("active_fullscreen", TabPartState::ActiveFullscreen),
+    ("inactive_floating", TabPartState::InactiveFloating),
+    ("active_floating", TabPartState::ActiveFloating),
+    ("activity", TabPartState::Activity),
+    ("bell", TabPartState::Bell),
+    ("left_more_tabs", TabPartState::LeftMoreTabs),
+    ("right_more_tabs", TabPartState::RightMoreTabs),
+    ("pager", TabPartState::Pager),
+];
+
+/// Tab states falling back to other states when they have no layout.
+const OPTIONAL_TAB_STATES: [TabPartState; 6] = [
+    TabPartState::InactiveFullscreen,
+    TabPartState::ActiveFullscreen,
+    TabPartState::InactiveFloating,
+    TabPartState::ActiveFloating,
+    TabPartState::Activity,
+    TabPartState::Bell,
+];
+
+const SWAP_STATES: [(&str, SwapLayoutState); 2] = [
+    ("non_dirty", SwapLayoutState::NonDirty),
+    ("dirty", SwapLayoutState::Dirty),
+];
+
+const KEY_HINT_STATES: [(&str, KeyHintState); 2] =
+    [("full", KeyHintState::Full), ("short", KeyHintState::Short)];
+
+const COMMAND_STATES: [(&str, CommandState); 3] = [
+    ("success", CommandState::Success),
+    ("failure", CommandState::Failure),
+    ("timeout", CommandState::Timeout),
+];
+
+const CLIENT_STATES: [(&str, ClientsState); 2] = [
+    ("tab", ClientsState::Tab),
+    ("session", ClientsState::Session),
+];
+
+/// Mode or state with a layout, layouts are keyed in settings by names of the states.
+pub trait Named: Copy + Eq + Hash + 'static {
+    /// Settings field holding the layouts.
+    const FIELD: &'static str;
+    /// Kind of the states shown in errors.
+    const KIND: &'static str;
+    const NAMES: &'static [(&'static str, Self)];
+    /// States falling back to other states when they have no layout.
+    const OPTIONAL: &'static [Self] = &[];
+
+    /// Returns key of the layout in plugin configuration, e.g. `tab_layouts.active`.
+    fn key(&self) -> String {
+        let name = Self::NAMES.iter().find(|(_, s)| s == self);
+        format!("{}.{}", Self::FIELD, name.map_or("", |(n, _)| n))
+    }
+}
+
+/// Actions without arguments which can be shown in key hints, besides `SwitchToMode(<mode>)`.
+static ACTIONS: [(&str, Action); 11] = [
+    ("Quit", Action::Quit),
+    ("Detach", Action::Detach),
+    ("CloseFocus", Action::CloseFocus),
+    ("ToggleFocusFullscreen", Action::ToggleFocusFullscreen),
+    ("TogglePaneFrames", Action::TogglePaneFrames),
+    ("ToggleFloatingPanes", Action::ToggleFloatingPanes),
+    ("ToggleActiveSyncTab", Action::ToggleActiveSyncTab),
+    ("CloseTab", Action::CloseTab),
+    ("GoToNextTab", Action::GoToNextTab),
+    ("GoToPreviousTab", Action::GoToPreviousTab),
+    ("ToggleTab", Action::ToggleTab),
+];
+
+impl Named for InputMode {
+    const FIELD: &'static str = "mode_layouts";
+    const KIND: &'static str = "mode";
+    const NAMES: &'static [(&'static str, Self)] = &MODES;
+}
+
+impl Named for TabPartState {
+    const FIELD: &'static str = "tab_layouts";
+    const KIND: &'static str = "tab state";
+    const NAMES: &'static [(&'static str, Self)] = &TAB_STATES;
+    const OPTIONAL: &'static [Self] = &OPTIONAL_TAB_STATES;
+}
+
+impl Named for SwapLayoutState {
+    const FIELD: &'static str = "swap_layouts";
+    const KIND: &'static str = "swap state";
+    const NAMES: &'static [(&'static str, Self)] = &SWAP_STATES;
+}
+
+impl Named for KeyHintState {
+    const FIELD: &'static str = "key_hint_layouts";
+    const KIND: &'static str = "keys state";
+    const NAMES: &'static [(&'static str, Self)] = &KEY_HINT_STATES;
+}
+
+impl Named for CommandState {
+    const FIELD: &'static str = "command_layouts";
+    const KIND: &'static str = "cmd state";
+    const NAMES: &'static [(&'static str, Self)] = &COMMAND_STATES;
+}
+
+impl Named for ClientsState {
+    const FIELD: &'static str = "client_layouts";
+    const KIND: &'static str = "clients state";
+    const NAMES: &'static [(&'static str, Self)] = &CLIENT_STATES;
+}
+
+/// Owned settings of the plugin, the compiled config and plugin configuration are merged into
+/// them. Unset fields are taken from the settings they are merged over, layout maps are merged
+/// by key and keyed by names of modes and states, e.g. `normal` or `active_sync`.
+#[derive(Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub layout: Option<String>,
+    pub mode_layouts: BTreeMap<String, String>,
+    pub tab_layouts: BTreeMap<String, String>,
+    pub tab_overflow: Option<TabOverflow>,
+    pub tab_name_min_len: Option<usize>,
+    pub collapsed_click: Option<CollapsedClick>,
+    pub mouse_actions: Option<Vec<(ClickTarget, MouseButton, MouseAction)>>,
+    pub tab_scroll: Option<TabScroll>,
+    pub tab_scroll_wrap: Option<bool>,
+    pub swap_layouts: BTreeMap<String, String>,
+    pub key_hints: Option<Vec<(String, String)>>,
+    pub key_hint_layouts: BTreeMap<String, String>,
+    pub utc_offset_minutes: Option<i32>,
+    pub commands: Option<Vec<CommandSettings>>,
+    pub command_layouts: BTreeMap<String, String>,
+    pub client_layouts: BTreeMap<String, String>,
+    /// Shows internal state of the bar, not set by the compiled config.
+    pub debug: Option<bool>,
+}
+
+impl From<Config<'_>> for Settings {
+    fn from(cfg: Config) -> Settings {
+        Settings {
+            layout: Some(cfg.layout.to_string()),
+            mode_layouts: to_named(&cfg.mode_layouts),
+            tab_layouts: to_named(&cfg.tab_layouts),
+            tab_overflow: Some(cfg.tab_overflow),
+            tab_name_min_len: Some(cfg.tab_name_min_len),
+            collapsed_click: Some(cfg.collapsed_click),
+            mouse_actions: Some(cfg.mouse_actions),
+            tab_scroll: Some(cfg.tab_scroll),
+            tab_scroll_wrap: Some(cfg.tab_scroll_wrap),
+            swap_layouts: to_named(&cfg.swap_layouts),
+            key_hints: Some(
+                cfg.key_hints
+                    .iter()
+                    .map(|(action, label)| (action.to_string(), label.to_string()))
+                    .collect(),
+            ),
+            key_hint_layouts: to_named(&cfg.key_hint_layouts),
+            utc_offset_minutes: Some(cfg.utc_offset_minutes),
+            commands: Some(cfg.commands.iter().map(CommandSettings::from).collect()),
+            command_layouts: to_named(&cfg.command_layouts),
+            client_layouts: to_named(&cfg.client_layouts),
+            debug: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Parses plugin configuration. Keys are names of settings, or paths of layouts like
+    /// `tab_layouts.active`, values are TOML values, or strings if they can't be parsed as the
+    /// setting value, so layouts don't need quotes. Invalid keys are skipped and reported.
+    pub fn from_plugin_config(
+        configuration: &BTreeMap<String, String>,
+    ) -> (Settings, Vec<ConfigError>) {
+        let mut res = Settings::default();
+        let mut errors = Vec::new();
+
+        for (key, value) in configuration {
+            let as_string = || Self::from_key(key, toml::Value::String(value.to_string()));
+            // the error of the TOML value is reported, it's more specific than the string one
+            let parsed = match parse_toml_value(value) {
+                Some(v) => Self::from_key(key, v).or_else(|e| as_string().map_err(|_| e)),
+                None => as_string(),
+            };
+            match parsed {
+                Ok(settings) => res = res.merge(settings),
+                Err(e) => errors.push(ConfigError::new(key, value, &e, None)),
+            }
+        }
+
+        (res, errors)
+    }
+
+    /// Deserializes settings with the only key set.
+    fn from_key(key: &str, value: toml::Value) -> Result<Settings, String> {
+        let value = match key.split_once('.') {
+            Some((key, name)) => {
+                let layouts = toml::Table::from_iter([(name.to_string(), value)]);
+                toml::Table::from_iter([(key.to_string(), toml::Value::Table(layouts))])
+            }
+            None => toml::Table::from_iter([(key.to_string(), value)]),
+        };
+        let value = toml::Value::Table(value);
+        value
+            .try_into()
+            .map_err(|e: toml::de::Error| e.message().to_string())
+    }
+
+    /// Returns settings with fields set in other replacing these ones.
+    pub fn merge(self, other: Settings) -> Settings {
+        Settings {
+            layout: other.layout.or(self.layout),
+            mode_layouts: merge_layouts(self.mode_layouts, other.mode_layouts),
+            tab_layouts: merge_layouts(self.tab_layouts, other.tab_layouts),
+            tab_overflow: other.tab_overflow.or(self.tab_overflow),
+            tab_name_min_len: other.tab_name_min_len.or(self.tab_name_min_len),
+            collapsed_click: other.collapsed_click.or(self.collapsed_click),
+            mouse_actions: other.mouse_actions.or(self.mouse_actions),
+            tab_scroll: other.tab_scroll.or(self.tab_scroll),
+            tab_scroll_wrap: other.tab_scroll_wrap.or(self.tab_scroll_wrap),
+            swap_layouts: merge_layouts(self.swap_layouts, other.swap_layouts),
+            key_hints: other.key_hints.or(self.key_hints),
+            key_hint_layouts: merge_layouts(self.key_hint_layouts, other.key_hint_layouts),
+            utc_offset_minutes: other.utc_offset_minutes.or(self.utc_offset_minutes),
+            commands: other.commands.or(self.commands),
+            command_layouts: merge_layouts(self.command_layouts, other.command_layouts),
+            client_layouts: merge_layouts(self.client_layouts, other.client_layouts),
+            debug: other.debug.or(self.debug),
+        }
+    }
+
+    /// Checks names of modes and states, and that every state without fallback has a layout.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = check_names::<InputMode>(&self.mode_layouts);
+        errors.extend(check_names::<TabPartState>(&self.tab_layouts));
+        errors.extend(check_names::<SwapLayoutState>(&self.swap_layouts));
+        errors.extend(check_names::<KeyHintState>(&self.key_hint_layouts));
+        errors.extend(check_names::<CommandState>(&self.command_layouts));
+        errors.extend(check_names::<ClientsState>(&self.client_layouts));
+        errors.extend(check_actions(self.key_hints.iter().flatten()));
+        errors
+    }
+
+    pub fn mode_layouts(&self) -> HashMap<InputMode, &str> {
+        from_named(&self.mode_layouts)
+    }
+
+    pub fn tab_layouts(&self) -> HashMap<TabPartState, &str> {
+        from_named(&self.tab_layouts)
+    }
+
+    pub fn swap_layouts(&self) -> HashMap<SwapLayoutState, &str> {
+        from_named(&self.swap_layouts)
+    }
+
+    pub fn key_hint_layouts(&self) -> HashMap<KeyHintState, &str> {
+        from_named(&self.key_hint_layouts)
+    }
+
+    pub fn command_layouts(&self) -> HashMap<CommandState, &str> {
+        from_named(&self.command_layouts)
+    }
+
+    pub fn client_layouts(&self) -> HashMap<ClientsState, &str> {
+        from_named(&self.client_layouts)
+    }
+
+    /// Returns key hints with actions compared to keybinds, hints of unknown actions are skipped.
+    pub fn key_hints(&self) -> Vec<(Action, String)> {
+        let hints = self.key_hints.iter().flatten();
+        let iter = hints.filter_map(|(action, label)| Some((parse_action(action)?, label.clone())));
+        iter.collect()
+    }
+}
+
+/// Parses the action written as zellij prints it, e.g. `SwitchToMode(Pane)` or `Quit`.
+fn parse_action(s: &str) -> Option<Action> {
+    if let Some(mode) = s.strip_prefix("SwitchToMode(") {
+        let mode = mode.strip_suffix(')')?.to_lowercase();
+        // modes are named in snake case, e.g. `enter_search` for `EnterSearch`
+        let (_, mode) = MODES.iter().find(|(n, _)| n.replace('_', "") == mode)?;
+        return Some(Action::SwitchToMode(*mode));
+    }
+    let (_, action) = ACTIONS.iter().find(|(n, _)| *n == s)?;
+    Some(action.clone())
+}
+
+fn check_actions<'a>(hints: impl Iterator<Item = &'a (String, String)>) -> Vec<ConfigError> {
+    let unknown = hints.filter(|(action, _)| parse_action(action).is_none());
+    let iter = unknown.map(|(action, _)| {
+        let names = ACTIONS.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+        let hint = format!("actions are SwitchToMode(<mode>), {}", names.join(", "));
+        ConfigError::new("key_hints", action, "Unknown action", Some(hint))
+    });
+    iter.collect()
+}
+
+/// Parses text as a TOML value, e.g. `4`, `true` or `[1, 2]`.
+fn parse_toml_value(s: &str) -> Option<toml::Value> {
+    let mut table = format!("value = {s}").parse::<toml::Table>().ok()?;
+    table.remove("value")
+}
+
+fn merge_layouts(
+    mut layouts: BTreeMap<String, String>,
+    other: BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    layouts.extend(other);
+    layouts
+}
+
+fn to_named<T: Named>(layouts: &HashMap<T, &str>) -> BTreeMap<String, String> {
+    let iter = T::NAMES.iter().filter_map(|(name, key)| {
+        let layout = layouts.get(key)?;
+        Some((name.to_string(), layout.to_string()))
+    });
+    iter.collect()
+}
+
+fn from_named<T: Named>(layouts: &BTreeMap<String, String>) -> HashMap<T, &str> {
+    let iter = T::NAMES.iter().filter_map(|(name, key)| {
+        let layout = layouts.get(*name)?;
+        Some((*key, layout.as_str()))
+    });
+    iter.collect()
+}
+
+fn check_names<T: Named>(layouts: &BTreeMap<String, String>) -> Vec<ConfigError> {
+    let names = T::NAMES.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+    let unknown = layouts.keys().filter(|k| !names.contains(&k.as_str()));
+    let mut errors: Vec<_> = unknown
+        .map(|name| {
+            let hint = format!("{}s are {}", T::KIND, names.join(", "));
+            let key = format!("{}.{name}", T::FIELD);
+            ConfigError::new(&key, name, &format!("Unknown {}", T::KIND), Some(hint))
+        })
+        .collect();
+
+    let required = T::NAMES.iter().filter(|(_, s)| !T::OPTIONAL.contains(s));
+    let missing = required.filter(|(name, _)| !layouts.contains_key(*name));
+    errors.extend(missing.map(|(_, state)| {
+        let message = format!("Missing layout of {}", T::KIND);
+        ConfigError::new(&state.key(), "", &message, None)
+    }));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin_config(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        let iter = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string()));
+        iter.collect()
+    }
+
+    #[test]
+    fn parses_plugin_config() {
+        let configuration = plugin_config(&[
+            ("layout", "#[fg:white] #S #T"),
+            ("tab_overflow", "carousel"),
+            ("tab_name_min_len", "6"),
+            ("tab_layouts.active", "#[bg:green] #N "),
+            ("mouse_actions", r#"[["tab", "right", "close_tab"]]"#),
+        ]);
+        let (settings, errors) = Settings::from_plugin_config(&configuration);
+        assert_eq!(errors, vec![]);
+
+        assert_eq!(settings.layout.as_deref(), Some("#[fg:white] #S #T"));
+        assert_eq!(settings.tab_overflow, Some(TabOverflow::Carousel));
+        assert_eq!(settings.tab_name_min_len, Some(6));
+        assert_eq!(settings.tab_layouts["active"], "#[bg:green] #N ");
+        assert_eq!(
+            settings.mouse_actions,
+            Some(vec![(
+                ClickTarget::Tab,
+                MouseButton::Right,
+                MouseAction::CloseTab
+            )])
+        );
+    }
+
+    #[test]
+    fn reports_invalid_plugin_config() {
+        let configuration = plugin_config(&[
+            ("tab_overflow", "sideways"),
+            ("tab_overflw", "carousel"),
+            ("tab_name_min_len", "6"),
+        ]);
+        let (settings, errors) = Settings::from_plugin_config(&configuration);
+
+        assert_eq!(settings.tab_name_min_len, Some(6));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].key, "tab_overflow");
+        assert_eq!(errors[0].value, "sideways");
+        assert_eq!(errors[1].key, "tab_overflw");
+    }
+
+    #[test]
+    fn reports_errors_of_toml_values() {
+        let configuration = plugin_config(&[
+            ("tab_name_min_len", "-1"),
+            ("mouse_actions", r#"[["tab", "left", "swtich"]]"#),
+        ]);
+        let (_, errors) = Settings::from_plugin_config(&configuration);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].message.contains("unknown variant `swtich`"));
+        assert!(errors[1].message.contains("integer `-1`"));
+    }
+
+    #[test]
+    fn merges_over_defaults() {
+        let defaults = Settings::from(Config::default());
+        let configuration = plugin_config(&[("tab_layouts.active", "#N"), ("layout", "#T")]);
+        let settings = defaults
+            .clone()
+            .merge(Settings::from_plugin_config(&configuration).0);
+
+        assert_eq!(settings.layout.as_deref(), Some("#T"));
+        assert_eq!(settings.tab_layouts["active"], "#N");
+        assert_eq!(
+            settings.tab_layouts["inactive"],
+            defaults.tab_layouts["inactive"]
+        );
+        assert_eq!(settings.tab_overflow, defaults.tab_overflow);
+        assert_eq!(settings.validate(), vec![]);
+    }
+
+    #[test]
+    fn parses_key_hint_actions() {
+        assert_eq!(
+            parse_action("SwitchToMode(EnterSearch)"),
+            Some(Action::SwitchToMode(InputMode::EnterSearch))
+        );
+        assert_eq!(parse_action("Quit"), Some(Action::Quit));
+        assert_eq!(parse_action("SwitchToMode(Pane"), None);
+        assert_eq!(parse_action("NewPane(None, None)"), None);
+
+        let mut settings = Settings::from(Config::default());
+        let hints = settings.key_hints.as_mut().unwrap();
+        hints.push(("Qiut".to_string(), "QUIT".to_string()));
+        let errors = settings.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            (errors[0].key.as_str(), errors[0].value.as_str()),
+            ("key_hints", "Qiut")
+        );
+        // the unknown action is skipped
+        assert_eq!(
+            settings.key_hints().len(),
+            Config::default().key_hints.len()
+        );
+    }
+
+    #[test]
+    fn validates_names() {
+        let mut settings = Settings::from(Config::default());
+        settings
+            .mode_layouts
+            .insert("nrmal".to_string(), "".to_string());
+        let errors = settings.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].key, "mode_layouts.nrmal");
+        assert_eq!(errors[0].message, "Unknown mode");
+
+        let mut settings = Settings::from(Config::default());
+        settings.tab_layouts.remove("pager");
+        assert_eq!(settings.validate()[0].key, "tab_layouts.pager");
+
+        let mut settings = Settings::from(Config::default());
+        settings.tab_layouts.remove("bell");
+        assert_eq!(settings.validate(), vec![]);
+    }
+}
@@ -0,0 +1,142 @@
+//! Renders every preset with synthetic state at several widths and compares the output with
+//! snapshots in `tests/snapshots`. Run with `UPDATE_SNAPSHOTS=1` to accept changes.
+
+use std::fs;
+use std::path::PathBuf;
+
+use zellij_tile::prelude::*;
+
+use crate::command::{CommandConfig, CommandSettings};
+use crate::host::FakeHost;
+use crate::sample;
+use crate::settings::Settings;
+use crate::State;
+
+const WIDTHS: [usize; 5] = [20, 40, 80, 120, 200];
+
+/// Thursday 2024-02-29 13:04:05 UTC.
+const NOW: f64 = 1709211845.0;
+
+/// Layout of components which the presets don't show, rendered over compact-bar settings.
+const COMPONENTS_LAYOUT: &str =
+    "#K#_#{cmd:git} #{var:build} #{pane.title} #{clients} #{date:%a %d %b} #{time}";
+
+fn state(settings: Settings) -> State<FakeHost> {
+    assert_eq!(settings.validate(), vec![]);
+    let state = sample::state(&settings, sample::palette(), sample::tabs(8, 2));
+    assert_eq!(state.errors, vec![]);
+    state
+}
+
+/// Replaces ANSI escape sequences with symbolic annotations like `<fg:2,bold>`.
+fn annotate_ansi(s: &str) -> String {
+    let mut res = String::new();
+    let mut iter = s.chars();
+
+    while let Some(c) = iter.next() {
+        if c != '\x1b' {
+            res.push(c);
+            continue;
+        }
+
+        let sequence = iter
+            .by_ref()
+            .skip(1) // '['
+            .take_while(|c| *c != 'm')
+            .collect::<String>();
+        let params = sequence.split(';').collect::<Vec<_>>();
+        let mut names = Vec::new();
+        let mut i = 0;
+        while i < params.len() {
+            match params[i..] {
+                ["38", "5", n, ..] | ["48", "5", n, ..] => {
+                    let side = if params[i] == "38" { "fg" } else { "bg" };
+                    names.push(format!("{side}:{n}"));
+                    i += 2;
+                }
+                ["0", ..] | ["", ..] => names.push("reset".to_string()),
+                ["1", ..] => names.push("bold".to_string()),
+                [p, ..] => names.push(p.to_string()),
+                [] => {}
+            }
+            i += 1;
+        }
+        res.push_str(&format!("<{}>", names.join(",")));
+    }
+
+    res
+}
+
+fn render(mut state: State<FakeHost>) -> String {
+    let mut res = String::new();
+    state.host.now = NOW;
+
+    for width in WIDTHS {
+        state.render(1, width);
+        res.push_str(&format!(
+            "width {width}:\n{}\n",
+            annotate_ansi(&state.host.output)
+        ));
+    }
+
+    res
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.snap"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "snapshot {name} differs, rerun with UPDATE_SNAPSHOTS=1 to accept\n\
+         expected:\n{expected}\nactual:\n{actual}"
+    );
+}
+
+#[test]
+fn compact_bar() {
+    assert_snapshot(
+        "compact-bar",
+        &render(state(sample::preset("compact-bar").unwrap())),
+    );
+}
+
+#[test]
+fn minimal() {
+    assert_snapshot(
+        "minimal",
+        &render(state(sample::preset("minimal").unwrap())),
+    );
+}
+
+#[test]
+fn template() {
+    assert_snapshot(
+        "_template",
+        &render(state(sample::preset("template").unwrap())),
+    );
+}
+
+#[test]
+fn components() {
+    let mut settings = sample::preset("compact-bar").unwrap();
+    settings.layout = Some(COMPONENTS_LAYOUT.to_string());
+    settings.commands = Some(vec![CommandSettings::from(&CommandConfig {
+        name: "git",
+        command: vec!["git", "branch", "--show-current"],
+        interval: 10.0,
+        timeout: 2.0,
+    })]);
+
+    let mut state = state(settings);
+    state.apply_message("set build passed");
+    assert_snapshot("components", &render(state));
+}
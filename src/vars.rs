@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+/// Values set by other tools through plugin messages.
+#[derive(Default)]
+pub struct Vars {
+    /// Value and optional expiration time in seconds since unix epoch.
+    values: HashMap<String, (String, Option<f64>)>,
+}
+
+impl Vars {
+    /// Applies messages of the form `set [-t <ttl>] <key> <value>` or `clear <key>`, one per
+    /// line, returns true if any value changed. Every line is parsed before any is applied, so
+    /// a message with an invalid line changes nothing.
+    pub fn apply(&mut self, message: &str, now: f64) -> Result<bool, String> {
+        let lines = message.lines().filter(|l| !l.trim().is_empty());
+        let commands = lines
+            .map(|line| parse_command(line, now))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut changed = false;
+        for command in commands {
+            match command {
+                Command::Set(key, value) => {
+                    changed |= self.values.get(key) != Some(&value);
+                    self.values.insert(key.to_string(), value);
+                }
+                Command::Clear(key) => changed |= self.values.remove(key).is_some(),
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Removes expired values, returns true if any was removed.
+    pub fn expire(&mut self, now: f64) -> bool {
+        let len = self.values.len();
+        self.values
+            .retain(|_, (_, expires_at)| !matches!(expires_at, Some(t) if *t <= now));
+        len != self.values.len()
+    }
+
+    pub fn has_expiring(&self) -> bool {
+        self.values
+            .values()
+            .any(|(_, expires_at)| expires_at.is_some())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|(value, _)| value.as_str())
+    }
+}
+
+enum Command<'a> {
+    /// Key, value and optional expiration time.
+    Set(&'a str, (String, Option<f64>)),
+    Clear(&'a str),
+}
+
+fn parse_command(line: &str, now: f64) -> Result<Command<'_>, String> {
+    let (command, rest) = split_word(line);
+    match command {
+        "set" => {
+            let (mut key, mut rest) = split_word(rest);
+            let mut expires_at = None;
+            if key == "-t" {
+                let (ttl, r) = split_word(rest);
+                let ttl = match ttl.parse::<f64>() {
+                    Ok(ttl) if ttl.is_finite() && ttl >= 0.0 => ttl,
+                    _ => return Err(format!("Invalid ttl: {ttl}")),
+                };
+                expires_at = Some(now + ttl);
+                (key, rest) = split_word(r);
+            }
+            if key.is_empty() {
+                return Err(format!("Missing key: {line}"));
+            }
+            Ok(Command::Set(key, (rest.to_string(), expires_at)))
+        }
+        "clear" => {
+            let (key, _) = split_word(rest);
+            if key.is_empty() {
+                return Err(format!("Missing key: {line}"));
+            }
+            Ok(Command::Clear(key))
+        }
+        _ => Err(format!("Unknown command: {line}")),
+    }
+}
+
+/// Splits the first whitespace separated word from the rest of the string.
+fn split_word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    match s.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (s, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_and_clears_values() {
+        let mut vars = Vars::default();
+        assert_eq!(vars.apply("set branch feature/vars x\n\n", 0.0), Ok(true));
+        assert_eq!(vars.get("branch"), Some("feature/vars x"));
+        assert_eq!(vars.apply("set branch feature/vars x", 0.0), Ok(false));
+        assert!(!vars.has_expiring());
+
+        assert_eq!(vars.apply("clear branch", 0.0), Ok(true));
+        assert_eq!(vars.get("branch"), None);
+        assert_eq!(vars.apply("clear branch", 0.0), Ok(false));
+    }
+
+    #[test]
+    fn expires_values() {
+        let mut vars = Vars::default();
+        assert_eq!(
+            vars.apply("set -t 5 build ok\nset deploy done", 10.0),
+            Ok(true)
+        );
+        assert!(vars.has_expiring());
+
+        assert!(!vars.expire(14.9));
+        assert!(vars.expire(15.0));
+        assert_eq!(vars.get("build"), None);
+        assert_eq!(vars.get("deploy"), Some("done"));
+        assert!(!vars.has_expiring());
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        let mut vars = Vars::default();
+        for message in [
+            "set -t nan build ok",
+            "set -t -1 build ok",
+            "set -t 5",
+            "set",
+            "clear",
+            "unset build",
+        ] {
+            assert!(vars.apply(message, 0.0).is_err(), "{message}");
+        }
+
+        // earlier lines are not applied either
+        assert!(vars.apply("set build ok\nunset deploy", 0.0).is_err());
+        assert_eq!(vars.get("build"), None);
+    }
+}